@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{Local, Timelike};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::models::{Alert, AlertKind, Settings, UsageLimits};
+
+const DAILY_SUMMARY_TICK: Duration = Duration::from_secs(5 * 60);
+
+/// Utilization and reset time of each limit window, keyed by window
+type WindowSnapshot = HashMap<&'static str, (f64, Option<String>)>;
+
+/// Last utilization and reset time seen for each limit window. `None` until
+/// the first poll, which only records a baseline so restarting the app
+/// doesn't repeat alerts that already fired.
+#[derive(Default)]
+pub struct AlertState {
+    windows: Mutex<Option<WindowSnapshot>>,
+}
+
+/// Compares freshly polled limits with the previous poll and sends an alert
+/// for every configured threshold crossed and every window that reset.
+pub fn check_limits(app: &AppHandle, limits: &UsageLimits) {
+    let current: WindowSnapshot = crate::tray::limit_windows(limits)
+        .map(|(window, _, entry)| (window, (entry.utilization, entry.resets_at.clone())))
+        .collect();
+    let Some(previous) = app
+        .state::<AlertState>()
+        .windows
+        .lock()
+        .unwrap()
+        .replace(current)
+    else {
+        return;
+    };
+
+    let settings = crate::settings::load();
+    for (window, label, entry) in crate::tray::limit_windows(limits) {
+        let Some((before, before_reset)) = previous.get(window) else {
+            continue;
+        };
+
+        if entry.utilization < *before && *before_reset != entry.resets_at {
+            deliver(
+                app,
+                &settings,
+                Alert {
+                    kind: AlertKind::Reset,
+                    title: format!("{label} limit reset"),
+                    message: format!(
+                        "Your {} limit has reset and is at {}%.",
+                        label.to_lowercase(),
+                        entry.utilization.round()
+                    ),
+                    window: Some(window.to_string()),
+                    utilization: Some(entry.utilization),
+                    resets_at: entry.resets_at.clone(),
+                    summary: None,
+                    digest: None,
+                    budget: None,
+                    timestamp: Local::now().to_rfc3339(),
+                },
+            );
+            continue;
+        }
+
+        let crossed = settings
+            .alert_thresholds
+            .iter()
+            .map(|t| f64::from(*t))
+            .filter(|t| *before < *t && entry.utilization >= *t)
+            .max_by(f64::total_cmp);
+        if let Some(threshold) = crossed {
+            let resets = entry
+                .resets_at
+                .as_deref()
+                .and_then(crate::tray::format_reset_countdown)
+                .map(|countdown| format!(" Resets in {countdown}."))
+                .unwrap_or_default();
+            deliver(
+                app,
+                &settings,
+                Alert {
+                    kind: AlertKind::Threshold,
+                    title: format!("{label} limit at {threshold}%"),
+                    message: format!(
+                        "You've used {}% of your {} limit.{resets}",
+                        entry.utilization.round(),
+                        label.to_lowercase()
+                    ),
+                    window: Some(window.to_string()),
+                    utilization: Some(entry.utilization),
+                    resets_at: entry.resets_at.clone(),
+                    summary: None,
+                    digest: None,
+                    budget: None,
+                    timestamp: Local::now().to_rfc3339(),
+                },
+            );
+        }
+    }
+}
+
+/// Sends today's summary once a day, at or after the configured hour.
+pub fn start_daily_summary(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            send_daily_summary_if_due(&app);
+            tokio::time::sleep(DAILY_SUMMARY_TICK).await;
+        }
+    });
+}
+
+fn send_daily_summary_if_due(app: &AppHandle) {
+    let mut settings = crate::settings::load();
+    let Some(hour) = settings.daily_summary_hour else {
+        return;
+    };
+    let now = Local::now();
+    let today = now.format("%Y-%m-%d").to_string();
+    if now.hour() < hour || settings.last_daily_summary.as_deref() == Some(today.as_str()) {
+        return;
+    }
+
+    let summary = match crate::commands::get_today_summary() {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("daily summary error: {e}");
+            return;
+        }
+    };
+
+    settings.last_daily_summary = Some(today);
+    if let Err(e) = crate::settings::save(&settings) {
+        eprintln!("Failed to save settings: {e}");
+        return;
+    }
+    // The dashboard holds a copy of the settings; keep it from writing back a stale date
+    let _ = app.emit("settings-updated", ());
+
+    deliver(
+        app,
+        &settings,
+        Alert {
+            kind: AlertKind::DailySummary,
+            title: "Claude Code today".to_string(),
+            message: format!(
+                "{} tokens, {} messages, {} sessions, {} tool calls.",
+                crate::tray::format_token_count(summary.total_tokens),
+                summary.messages,
+                summary.sessions,
+                summary.tool_calls
+            ),
+            window: None,
+            utilization: None,
+            resets_at: None,
+            summary: Some(summary),
+            digest: None,
+            budget: None,
+            timestamp: now.to_rfc3339(),
+        },
+    );
+}
+
+/// Shows the alert as a desktop notification (when enabled) and posts it to
+/// the configured webhooks.
+pub fn deliver(app: &AppHandle, settings: &Settings, alert: Alert) {
+    notify(app, settings, &alert);
+    crate::webhook::dispatch(&settings.webhooks, &alert);
+}
+
+/// Shows the alert as a desktop notification, if notifications are enabled.
+pub fn notify(app: &AppHandle, settings: &Settings, alert: &Alert) {
+    if settings.notifications_enabled {
+        let _ = app
+            .notification()
+            .builder()
+            .title(&alert.title)
+            .body(&alert.message)
+            .show();
+    }
+}
+
+pub fn test_alert() -> Alert {
+    Alert {
+        kind: AlertKind::Test,
+        title: "Brew Status test alert".to_string(),
+        message: "Webhook alerts from Brew Status are working.".to_string(),
+        window: Some("five_hour".to_string()),
+        utilization: Some(42.0),
+        resets_at: None,
+        summary: None,
+        digest: None,
+        budget: None,
+        timestamp: Local::now().to_rfc3339(),
+    }
+}
@@ -0,0 +1,197 @@
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tiny_http::{Method, Request};
+
+use crate::http::{self, LocalServer};
+use crate::models::{SessionSummary, Settings};
+use crate::sessions::SessionIndex;
+
+const TOKEN_FILE: &str = "api-token";
+const DEFAULT_SESSIONS: usize = 20;
+const MAX_SESSIONS: usize = 200;
+
+/// Opt-in JSON API on `127.0.0.1:<api_port>`. Every request must carry
+/// `Authorization: Bearer <token>` with the token from [`token_path`].
+#[derive(Default)]
+pub struct ApiServer {
+    server: LocalServer,
+}
+
+/// Starts, moves or stops the API to match the settings.
+pub fn sync(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let port = settings.api_enabled.then_some(settings.api_port);
+    let token = match port {
+        Some(_) => Some(load_or_create_token()?),
+        None => None,
+    };
+    let handle = app.clone();
+
+    app.state::<ApiServer>()
+        .server
+        .sync("local API", port, move |request| {
+            let token = token.as_deref().unwrap_or_default();
+            handle_request(&handle, token, request)
+        })
+}
+
+pub fn token_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("com.brewstatus").join(TOKEN_FILE))
+}
+
+/// Reuses the existing token so scripts keep working across restarts.
+fn load_or_create_token() -> Result<String, String> {
+    let path = token_path().ok_or_else(|| "Could not find data directory".to_string())?;
+    if let Ok(token) = fs::read_to_string(&path) {
+        let token = token.trim();
+        if !token.is_empty() {
+            return Ok(token.to_string());
+        }
+    }
+
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate API token: {e}"))?;
+    let token: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    // An empty leftover would make `create_new` fail
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            return Err(format!("Failed to replace {}: {e}", path.display()));
+        }
+        _ => {}
+    }
+    // Created owner-only from the start so the token is never readable by others
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&path)
+        .and_then(|mut file| file.write_all(token.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+    Ok(token)
+}
+
+/// Compares without stopping at the first difference, so response times
+/// don't reveal how much of a guessed token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn handle_request(app: &AppHandle, token: &str, request: Request) {
+    let authorized = request.headers().iter().any(|h| {
+        h.field.equiv("Authorization")
+            && h.value
+                .as_str()
+                .strip_prefix("Bearer ")
+                .is_some_and(|given| constant_time_eq(given.trim().as_bytes(), token.as_bytes()))
+    });
+    if !authorized {
+        return error(request, 401, "Missing or invalid bearer token");
+    }
+    if *request.method() != Method::Get {
+        return error(request, 405, "Only GET is supported");
+    }
+
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+    };
+    let number = |name: &str| param(name).and_then(|v| v.parse::<usize>().ok());
+
+    let result = match path.trim_end_matches('/') {
+        "/today" => json(crate::commands::get_today_summary()),
+        "/stats" => json(crate::commands::get_stats_cache()),
+        "/limits" => match crate::tray::latest_limits(app) {
+            Some(limits) => json(Ok(limits)),
+            None => Err((503, "Usage limits haven't been fetched yet".to_string())),
+        },
+        "/history" => json(
+            crate::history::page(
+                &crate::commands::claude_dir().join("history.jsonl"),
+                param("cursor").as_deref(),
+                number("limit").unwrap_or(50),
+            )
+            .map(|mut page| {
+                app.state::<SessionIndex>().enrich(&mut page.entries);
+                page
+            }),
+        ),
+        "/sessions" => json(Ok(recent_sessions(
+            app,
+            number("limit").unwrap_or(DEFAULT_SESSIONS),
+        ))),
+        other => match other.strip_prefix("/sessions/") {
+            // Session ids are UUIDs; anything else could escape the projects dir
+            Some(session_id)
+                if session_id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-') =>
+            {
+                match crate::sessions::find_transcript(None, session_id) {
+                    Some(path) => json(crate::sessions::transcript(
+                        &path,
+                        number("offset").unwrap_or(0),
+                        number("limit"),
+                    )),
+                    None => Err((404, format!("No transcript found for session {session_id}"))),
+                }
+            }
+            _ => Err((404, "Not found".to_string())),
+        },
+    };
+
+    match result {
+        Ok(body) => http::respond(request, 200, "application/json", body),
+        Err((status, message)) => error(request, status, &message),
+    }
+}
+
+/// Summaries of the most recently active sessions, newest first.
+fn recent_sessions(app: &AppHandle, limit: usize) -> Vec<SessionSummary> {
+    let mut paths: Vec<(std::time::SystemTime, PathBuf)> = crate::commands::session_paths()
+        .into_iter()
+        .filter(|path| !crate::commands::is_subagent_transcript(path))
+        .filter_map(|path| Some((fs::metadata(&path).ok()?.modified().ok()?, path)))
+        .collect();
+    paths.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+    let index = app.state::<SessionIndex>();
+    paths
+        .into_iter()
+        .take(limit.clamp(1, MAX_SESSIONS))
+        .filter_map(|(_, path)| {
+            index
+                .summary(&path)
+                .map_err(|e| eprintln!("session summary error: {e}"))
+                .ok()
+        })
+        .collect()
+}
+
+fn json<T: Serialize>(result: Result<T, String>) -> Result<String, (u16, String)> {
+    result
+        .map_err(|e| (500, e))
+        .and_then(|value| serde_json::to_string(&value).map_err(|e| (500, e.to_string())))
+}
+
+fn error(request: Request, status: u16, message: &str) {
+    let body = serde_json::json!({ "error": message }).to_string();
+    http::respond(request, status, "application/json", body);
+}
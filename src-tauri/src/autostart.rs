@@ -0,0 +1,195 @@
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use std::fs;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use std::path::Path;
+use std::path::PathBuf;
+#[cfg(target_os = "windows")]
+use std::process::Command;
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+const APP_ID: &str = "com.brewstatus";
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(target_os = "windows")]
+const RUN_VALUE: &str = "Brew Status";
+
+/// Makes the OS login items match the persisted setting. Called at startup too,
+/// so an app that moved (or an AppImage that was replaced) re-registers itself
+/// at its current location.
+pub fn sync(enabled: bool) -> Result<(), String> {
+    if enabled {
+        enable()
+    } else if is_enabled() {
+        disable()
+    } else {
+        Ok(())
+    }
+}
+
+pub fn executable_path() -> Result<PathBuf, String> {
+    // AppImages run from a temporary mount; the stable path is in $APPIMAGE
+    #[cfg(target_os = "linux")]
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+
+    std::env::current_exe().map_err(|e| format!("Failed to locate executable: {e}"))
+}
+
+#[cfg(target_os = "windows")]
+fn enable() -> Result<(), String> {
+    let exe = executable_path()?;
+    let command = format!("\"{}\"", exe.display());
+    let output = Command::new("reg")
+        .args([
+            "add", RUN_KEY, "/v", RUN_VALUE, "/t", "REG_SZ", "/d", &command, "/f",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run reg.exe: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to add login item: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn disable() -> Result<(), String> {
+    let output = Command::new("reg")
+        .args(["delete", RUN_KEY, "/v", RUN_VALUE, "/f"])
+        .output()
+        .map_err(|e| format!("Failed to run reg.exe: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to remove login item: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn is_enabled() -> bool {
+    Command::new("reg")
+        .args(["query", RUN_KEY, "/v", RUN_VALUE])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(target_os = "macos")]
+fn launch_agent_path() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|home| {
+            home.join("Library")
+                .join("LaunchAgents")
+                .join(format!("{APP_ID}.plist"))
+        })
+        .ok_or_else(|| "Could not find home directory".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn enable() -> Result<(), String> {
+    let exe = executable_path()?;
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{APP_ID}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>ProcessType</key>
+    <string>Interactive</string>
+</dict>
+</plist>
+"#,
+        xml_escape(&exe.to_string_lossy())
+    );
+
+    write_entry(&launch_agent_path()?, &plist)
+}
+
+#[cfg(target_os = "macos")]
+fn disable() -> Result<(), String> {
+    remove_entry(&launch_agent_path()?)
+}
+
+#[cfg(target_os = "macos")]
+fn is_enabled() -> bool {
+    launch_agent_path().is_ok_and(|path| path.exists())
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_entry_path() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|config| config.join("autostart").join(format!("{APP_ID}.desktop")))
+        .ok_or_else(|| "Could not find config directory".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn enable() -> Result<(), String> {
+    let exe = executable_path()?;
+    // Exec values are quoted per the Desktop Entry spec so paths with spaces
+    // work. Backslashes are escaped twice (once for the quoting, once as a
+    // string value) and `%` is doubled so it isn't read as a field code.
+    let exec = exe
+        .to_string_lossy()
+        .replace('\\', "\\\\\\\\")
+        .replace('"', "\\\"")
+        .replace('`', "\\`")
+        .replace('$', "\\$")
+        .replace('%', "%%");
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Brew Status\n\
+         Comment=Claude Code usage in your tray\n\
+         Exec=\"{exec}\"\n\
+         Icon=brew-status\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n"
+    );
+
+    write_entry(&desktop_entry_path()?, &entry)
+}
+
+#[cfg(target_os = "linux")]
+fn disable() -> Result<(), String> {
+    remove_entry(&desktop_entry_path()?)
+}
+
+#[cfg(target_os = "linux")]
+fn is_enabled() -> bool {
+    desktop_entry_path().is_ok_and(|path| path.exists())
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn write_entry(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn remove_entry(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {}: {e}", path.display())),
+    }
+}
@@ -0,0 +1,316 @@
+use std::io::{IsTerminal, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, TimeZone};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::models::{
+    Alert, AlertKind, Budget, BudgetLevel, BudgetPeriod, BudgetStatus, BudgetUnit,
+};
+use crate::usage::UsageRecord;
+
+/// Budgets re-read the period's transcripts, which change many times per
+/// second while Claude Code is working, so activity is batched
+const REFRESH_DEBOUNCE: Duration = Duration::from_secs(60);
+/// Claude Code hooks treat exit code 2 as "block, and show stderr to Claude"
+const HOOK_BLOCK_EXIT_CODE: i32 = 2;
+
+/// Latest evaluation of the configured budgets. `None` until the first one,
+/// which only records a baseline so restarting the app doesn't repeat
+/// notifications.
+#[derive(Default)]
+pub struct BudgetState {
+    statuses: Mutex<Option<Vec<BudgetStatus>>>,
+    refresh_pending: AtomicBool,
+}
+
+impl BudgetPeriod {
+    /// First day of the period containing `today`, and of the one after it.
+    fn bounds(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            BudgetPeriod::Daily => (today, today + Days::new(1)),
+            BudgetPeriod::Weekly => {
+                let monday = today - Days::new(u64::from(today.weekday().num_days_from_monday()));
+                (monday, monday + Days::new(7))
+            }
+            BudgetPeriod::Monthly => {
+                let first = today.with_day(1).unwrap_or(today);
+                (first, first + Months::new(1))
+            }
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            BudgetPeriod::Daily => "today",
+            BudgetPeriod::Weekly => "this week",
+            BudgetPeriod::Monthly => "this month",
+        }
+    }
+}
+
+impl BudgetUnit {
+    pub fn format(self, amount: f64) -> String {
+        match self {
+            BudgetUnit::Usd => format!("${amount:.2}"),
+            BudgetUnit::Tokens => crate::tray::format_token_count(amount.max(0.0) as u64),
+        }
+    }
+}
+
+/// Compares each enabled budget with the usage in its current period.
+pub fn evaluate(budgets: &[Budget]) -> Vec<BudgetStatus> {
+    let budgets: Vec<&Budget> = budgets
+        .iter()
+        .filter(|b| b.enabled && b.limit > 0.0)
+        .collect();
+    if budgets.is_empty() {
+        return Vec::new();
+    }
+
+    let today = Local::now().date_naive();
+    let since = budgets
+        .iter()
+        .map(|b| b.period.bounds(today).0)
+        .min()
+        .and_then(local_midnight);
+    let records = crate::usage::scan_usage_since(since);
+
+    budgets
+        .into_iter()
+        .map(|budget| status(budget, &records, today))
+        .collect()
+}
+
+fn status(budget: &Budget, records: &[UsageRecord], today: NaiveDate) -> BudgetStatus {
+    let (start, next) = budget.period.bounds(today);
+    let used: f64 = records
+        .iter()
+        .filter(|r| r.timestamp.date_naive() >= start && applies(budget, r))
+        .fold(0.0, |sum, r| {
+            sum + match budget.unit {
+                BudgetUnit::Usd => r.cost_usd(),
+                BudgetUnit::Tokens => r.total_tokens() as f64,
+            }
+        });
+    let percent = used / budget.limit * 100.0;
+    let level = if percent >= 100.0 {
+        BudgetLevel::Exceeded
+    } else if percent >= f64::from(budget.warn_percent) {
+        BudgetLevel::Warning
+    } else {
+        BudgetLevel::Ok
+    };
+
+    BudgetStatus {
+        budget: budget.clone(),
+        used,
+        percent,
+        level,
+        period_start: start.format("%Y-%m-%d").to_string(),
+        resets_at: local_midnight(next)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default(),
+    }
+}
+
+fn applies(budget: &Budget, record: &UsageRecord) -> bool {
+    let model = budget.model.as_deref().map(str::trim).unwrap_or_default();
+    matches_project(budget, &record.project)
+        && (model.is_empty() || record.model.to_lowercase().contains(&model.to_lowercase()))
+}
+
+/// Budgets name a project by directory name or full path.
+fn matches_project(budget: &Budget, project: &str) -> bool {
+    let filter = budget.project.as_deref().map(str::trim).unwrap_or_default();
+    filter.is_empty()
+        || project.trim_end_matches(['/', '\\']) == filter.trim_end_matches(['/', '\\'])
+        || crate::history::project_name(project).eq_ignore_ascii_case(filter)
+}
+
+fn local_midnight(date: NaiveDate) -> Option<DateTime<Local>> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+}
+
+fn display_name(budget: &Budget) -> String {
+    if budget.name.trim().is_empty() {
+        let period = match budget.period {
+            BudgetPeriod::Daily => "Daily",
+            BudgetPeriod::Weekly => "Weekly",
+            BudgetPeriod::Monthly => "Monthly",
+        };
+        format!("{period} budget")
+    } else {
+        budget.name.trim().to_string()
+    }
+}
+
+/// e.g. `$6.20 of $5.00 used today (124%)`
+fn describe_usage(status: &BudgetStatus) -> String {
+    let unit = status.budget.unit;
+    format!(
+        "{} of {} used {} ({}%)",
+        unit.format(status.used),
+        unit.format(status.budget.limit),
+        status.budget.period.describe(),
+        status.percent.round()
+    )
+}
+
+/// Last evaluation, for the tray menu; empty before the first one.
+pub fn latest(app: &AppHandle) -> Vec<BudgetStatus> {
+    app.state::<BudgetState>()
+        .statuses
+        .lock()
+        .unwrap()
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Last evaluation, evaluating now if there hasn't been one yet.
+pub fn statuses(app: &AppHandle) -> Vec<BudgetStatus> {
+    let cached = app.state::<BudgetState>().statuses.lock().unwrap().clone();
+    match cached {
+        Some(statuses) => statuses,
+        None => {
+            refresh(app);
+            latest(app)
+        }
+    }
+}
+
+/// Re-evaluates budgets once a burst of session activity has settled.
+pub fn schedule_refresh(app: &AppHandle) {
+    let state = app.state::<BudgetState>();
+    if state.refresh_pending.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(REFRESH_DEBOUNCE);
+        app.state::<BudgetState>()
+            .refresh_pending
+            .store(false, Ordering::SeqCst);
+        refresh(&app);
+    });
+}
+
+/// Re-evaluates budgets in the background right away, e.g. after they were
+/// edited.
+pub fn refresh_now(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || refresh(&app));
+}
+
+/// Re-evaluates every budget and notifies about each one that moved up to
+/// warning or exceeded.
+fn refresh(app: &AppHandle) {
+    let settings = crate::settings::load();
+    let current = evaluate(&settings.budgets);
+    let previous = app
+        .state::<BudgetState>()
+        .statuses
+        .lock()
+        .unwrap()
+        .replace(current.clone());
+
+    crate::tray::rebuild_menu(app);
+    let _ = app.emit("budgets-updated", ());
+
+    let Some(previous) = previous else {
+        return;
+    };
+    for status in current {
+        let before = previous
+            .iter()
+            .find(|p| p.budget == status.budget && p.period_start == status.period_start)
+            .map_or(BudgetLevel::Ok, |p| p.level);
+        if status.level <= before {
+            continue;
+        }
+
+        let name = display_name(&status.budget);
+        let title = match status.level {
+            BudgetLevel::Exceeded => format!("{name} exceeded"),
+            _ => format!("{name} at {}%", status.percent.round()),
+        };
+        crate::alerts::deliver(
+            app,
+            &settings,
+            Alert {
+                kind: AlertKind::Budget,
+                title,
+                message: format!("{}.", describe_usage(&status)),
+                window: None,
+                utilization: Some(status.percent),
+                resets_at: Some(status.resets_at.clone()),
+                summary: None,
+                digest: None,
+                budget: Some(status),
+                timestamp: Local::now().to_rfc3339(),
+            },
+        );
+    }
+}
+
+/// Tray menu label for a budget that needs attention.
+pub fn menu_label(status: &BudgetStatus) -> String {
+    let state = match status.level {
+        BudgetLevel::Exceeded => "exceeded",
+        _ => "warning",
+    };
+    format!(
+        "{}: {} of {} ({state})",
+        display_name(&status.budget),
+        status.budget.unit.format(status.used),
+        status.budget.unit.format(status.budget.limit)
+    )
+}
+
+/// Entry point for `brew-status check-budget`, meant to run as a Claude Code
+/// `PreToolUse` or `UserPromptSubmit` hook. Exits with 2, which blocks the
+/// tool call or prompt, while any blocking budget that applies to the hook's
+/// working directory is exceeded.
+pub fn check_hook() -> i32 {
+    let cwd = hook_cwd();
+    let budgets: Vec<Budget> = crate::settings::load()
+        .budgets
+        .into_iter()
+        .filter(|b| b.block && cwd.as_deref().is_none_or(|cwd| matches_project(b, cwd)))
+        .collect();
+
+    let exceeded: Vec<String> = evaluate(&budgets)
+        .iter()
+        .filter(|s| s.level == BudgetLevel::Exceeded)
+        .map(|s| format!("{}: {}", display_name(&s.budget), describe_usage(s)))
+        .collect();
+    if exceeded.is_empty() {
+        return 0;
+    }
+
+    eprintln!(
+        "Over budget. {}. Raise the limit or turn off blocking in Brew Status to continue.",
+        exceeded.join("; ")
+    );
+    HOOK_BLOCK_EXIT_CODE
+}
+
+/// Working directory from the JSON Claude Code passes to hooks on stdin.
+fn hook_cwd() -> Option<String> {
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return None;
+    }
+    let mut input = String::new();
+    stdin.read_to_string(&mut input).ok()?;
+    serde_json::from_str::<serde_json::Value>(&input)
+        .ok()?
+        .get("cwd")?
+        .as_str()
+        .map(ToString::to_string)
+}
@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::models::{CacheReport, CacheStats, TimeRange};
+use crate::usage::{percent, UsageRecord};
+
+/// Smaller writes are just the newest turn being appended to the cache
+const MIN_REBUILD_TOKENS: u64 = 10_000;
+/// A session rebuilding its cache this often is worth a look (long idle gaps
+/// letting the 5-minute cache expire, or a prompt prefix that keeps changing)
+const REPEATED_REBUILDS: u64 = 3;
+
+impl CacheStats {
+    fn new(key: &str, label: &str) -> Self {
+        CacheStats {
+            key: key.to_string(),
+            label: label.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, record: &UsageRecord, rebuild: bool) {
+        self.input_tokens += record.input_tokens;
+        self.cache_read_tokens += record.cache_read_input_tokens;
+        self.cache_write_tokens += record.cache_creation_input_tokens;
+        self.cache_write_5m_tokens += record.cache_creation_5m_tokens;
+        self.cache_write_1h_tokens += record.cache_creation_1h_tokens;
+        self.savings_usd += savings(record);
+        if rebuild {
+            self.rebuilds += 1;
+            self.rebuild_tokens += record.cache_creation_input_tokens;
+        }
+        self.hit_ratio = percent(self.cache_read_tokens, self.prompt_tokens());
+    }
+
+    fn prompt_tokens(&self) -> u64 {
+        self.input_tokens + self.cache_read_tokens + self.cache_write_tokens
+    }
+}
+
+/// What cache reads saved over paying full input price for the same tokens,
+/// minus the premium cache writes carry over plain input.
+fn savings(record: &UsageRecord) -> f64 {
+    let Some(price) = crate::pricing::price_for(&record.model) else {
+        return 0.0;
+    };
+    let (writes_5m, writes_1h) = crate::pricing::split_cache_writes(
+        record.cache_creation_input_tokens,
+        record.cache_creation_1h_tokens,
+    );
+    (record.cache_read_input_tokens as f64 * (price.input - price.cache_read)
+        - writes_5m as f64 * (price.cache_write - price.input)
+        - writes_1h as f64 * (price.cache_write_1h - price.input))
+        / 1_000_000.0
+}
+
+/// Cache hit ratio, savings and rebuilds over the range, overall and per day,
+/// project and session.
+pub fn report(range: &TimeRange) -> Result<CacheReport, String> {
+    let (from, to, scan) = crate::usage::scan_range(range)?;
+
+    let mut totals = CacheStats::new("", "");
+    let mut daily: BTreeMap<String, CacheStats> = BTreeMap::new();
+    let mut projects: HashMap<String, CacheStats> = HashMap::new();
+    let mut sessions: HashMap<String, CacheStats> = HashMap::new();
+    // Subagents keep their own cache, so each conversation's first turn is
+    // tracked separately
+    let mut started: HashSet<(&str, Option<&str>)> = HashSet::new();
+    for record in &scan.records {
+        let first_turn = started.insert((&record.session_id, record.agent_id.as_deref()));
+        let rebuild = !first_turn
+            && record.cache_creation_input_tokens >= MIN_REBUILD_TOKENS
+            && record.cache_creation_input_tokens > record.cache_read_input_tokens;
+
+        let date = record.timestamp.format("%Y-%m-%d").to_string();
+        totals.add(record, rebuild);
+        daily
+            .entry(date.clone())
+            .or_insert_with(|| CacheStats::new(&date, ""))
+            .add(record, rebuild);
+        projects
+            .entry(record.project.clone())
+            .or_insert_with(|| CacheStats::new(&record.project, ""))
+            .add(record, rebuild);
+        sessions
+            .entry(record.session_id.clone())
+            .or_insert_with(|| CacheStats::new(&record.session_id, &record.project))
+            .add(record, rebuild);
+    }
+
+    let by_prompt = |rows: HashMap<String, CacheStats>| {
+        let mut rows: Vec<CacheStats> = rows.into_values().collect();
+        rows.sort_by(|a, b| {
+            b.prompt_tokens()
+                .cmp(&a.prompt_tokens())
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        rows
+    };
+    let sessions = by_prompt(sessions);
+    let mut rebuilding_sessions: Vec<CacheStats> = sessions
+        .iter()
+        .filter(|s| s.rebuilds >= REPEATED_REBUILDS)
+        .cloned()
+        .collect();
+    rebuilding_sessions.sort_by_key(|s| std::cmp::Reverse(s.rebuild_tokens));
+
+    Ok(CacheReport {
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        totals,
+        daily: daily.into_values().collect(),
+        projects: by_prompt(projects),
+        sessions,
+        rebuilding_sessions,
+    })
+}
@@ -1,19 +1,24 @@
+use crate::history::HistoryIndex;
 use crate::models::{
-    Credentials, DailyActivity, DailyModelTokens, HistoryEntry, LongestSession, ModelUsage,
-    ProfileResponse, StatsCache, TodaySummary, UpdateResult, UsageLimits,
+    BudgetStatus, CacheReport, Credentials, DailyActivity, DailyModelTokens, DigestPeriod,
+    ExportRequest, ExtraUsageHistory, FiveHourWindow, HistoryEntry, HistoryPage,
+    HistorySearchQuery, LongestSession, McpUsage, ModelMix, ModelUsage, ProfileResponse, Settings,
+    StatsCache, SubagentUsage, TimeRange, Timeseries, TimeseriesBucket, TimeseriesMetric,
+    TodaySummary, ToolUsage, TranscriptPage, UpdateResult, UsageLimits, WebhookConfig,
+    WeeklyPattern,
 };
-use chrono::{DateTime, Local, Timelike};
+use crate::sessions::SessionIndex;
+use chrono::{DateTime, Datelike, Local, Timelike};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
-#[cfg(target_os = "macos")]
+use std::path::{Path, PathBuf};
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 use std::process::Command;
-use tauri::{AppHandle, LogicalSize, Manager, Size};
-use tauri_plugin_positioner::{Position, WindowExt};
-use tauri_plugin_updater::UpdaterExt;
+use tauri::{AppHandle, Emitter, LogicalSize, Manager, Size, State};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_opener::OpenerExt;
 
-fn claude_dir() -> PathBuf {
+pub(crate) fn claude_dir() -> PathBuf {
     dirs::home_dir()
         .expect("Could not find home directory")
         .join(".claude")
@@ -54,259 +59,116 @@ pub fn get_today_summary() -> Result<TodaySummary, String> {
 
 #[derive(Default)]
 struct SessionAgg {
-    first_ts_ms: Option<i64>,
-    first_ts_iso: Option<String>,
-    last_ts_ms: Option<i64>,
+    first: Option<DateTime<Local>>,
+    last: Option<DateTime<Local>>,
     message_count: u64,
 }
 
-#[derive(Default)]
-struct AssistantMsgAgg {
-    session_id: String,
-    date: String,
-    hour: u32,
-    timestamp_ms: i64,
-    timestamp_iso: String,
-    model: String,
-    input_tokens: u64,
-    output_tokens: u64,
-    cache_read_input_tokens: u64,
-    cache_creation_input_tokens: u64,
-    web_search_requests: u64,
-    tool_uses: HashSet<String>,
-}
-
-fn session_paths() -> Vec<PathBuf> {
+/// Every transcript under `~/.claude/projects`, including subagent
+/// transcripts Claude Code nests under `<project>/<session-id>/`.
+pub(crate) fn session_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
     let projects_dir = claude_dir().join("projects");
     if let Ok(projects) = fs::read_dir(projects_dir) {
         for project_entry in projects.flatten() {
-            if !project_entry.path().is_dir() {
-                continue;
-            }
-            if let Ok(files) = fs::read_dir(project_entry.path()) {
-                for file_entry in files.flatten() {
-                    let path = file_entry.path();
-                    let is_jsonl = path
-                        .extension()
-                        .and_then(|ext| ext.to_str())
-                        .is_some_and(|ext| ext.eq_ignore_ascii_case("jsonl"));
-                    if is_jsonl {
-                        paths.push(path);
-                    }
-                }
+            if project_entry.path().is_dir() {
+                collect_transcripts(&project_entry.path(), &mut paths);
             }
         }
     }
     paths
 }
 
-fn parse_local_timestamp(value: &serde_json::Value) -> Option<DateTime<Local>> {
-    value
-        .get("timestamp")
-        .and_then(|t| t.as_str())
-        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-        .map(|dt| dt.with_timezone(&Local))
+pub(crate) fn collect_transcripts(dir: &Path, paths: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Symlinked directories are skipped so a loop can't recurse forever
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        if is_dir {
+            collect_transcripts(&path, paths);
+            continue;
+        }
+        let is_jsonl = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jsonl"));
+        if is_jsonl {
+            paths.push(path);
+        }
+    }
 }
 
-fn update_session_agg(
-    sessions: &mut HashMap<String, SessionAgg>,
-    session_id: &str,
-    timestamp_ms: i64,
-    timestamp_iso: &str,
-) {
-    let session = sessions.entry(session_id.to_string()).or_default();
-
-    if session.first_ts_ms.is_none_or(|v| timestamp_ms < v) {
-        session.first_ts_ms = Some(timestamp_ms);
-        session.first_ts_iso = Some(timestamp_iso.to_string());
-    }
-    if session.last_ts_ms.is_none_or(|v| timestamp_ms > v) {
-        session.last_ts_ms = Some(timestamp_ms);
+/// Project directory name and session id of a transcript, going by its path.
+/// Subagent transcripts nested under `<project>/<session-id>/` belong to that
+/// session.
+pub(crate) fn transcript_owner(path: &Path) -> (String, String) {
+    let name = |component: Option<&std::ffi::OsStr>| {
+        component
+            .and_then(|c| c.to_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let relative = path
+        .strip_prefix(claude_dir().join("projects"))
+        .map(|relative| relative.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    match relative.as_slice() {
+        [project, session_id, _, ..] => (name(Some(project)), name(Some(session_id))),
+        _ => (
+            name(path.parent().and_then(|p| p.file_name())),
+            name(path.file_stem()),
+        ),
     }
-    session.message_count += 1;
+}
+
+/// Whether a transcript is a subagent's rather than a main session's.
+pub(crate) fn is_subagent_transcript(path: &Path) -> bool {
+    path.strip_prefix(claude_dir().join("projects"))
+        .is_ok_and(|relative| relative.components().count() > 2)
 }
 
 fn compute_stats_cache_from_sessions() -> Result<StatsCache, String> {
-    let mut user_ids_seen: HashSet<String> = HashSet::new();
-    let mut assistant_messages: HashMap<String, AssistantMsgAgg> = HashMap::new();
+    Ok(stats_cache_from_scan(&crate::usage::scan(None)))
+}
 
-    let mut daily_user_messages: HashMap<String, u64> = HashMap::new();
-    let mut daily_assistant_messages: HashMap<String, u64> = HashMap::new();
+/// Builds the stats cache Claude Code would write from a transcript scan.
+fn stats_cache_from_scan(scan: &crate::usage::UsageScan) -> StatsCache {
+    let mut daily_messages: HashMap<String, u64> = HashMap::new();
     let mut daily_tool_calls: HashMap<String, u64> = HashMap::new();
     let mut daily_sessions: HashMap<String, HashSet<String>> = HashMap::new();
 
     let mut daily_model_tokens: HashMap<String, HashMap<String, u64>> = HashMap::new();
     let mut model_usage: HashMap<String, ModelUsage> = HashMap::new();
 
-    let mut hour_counts: HashMap<String, u64> = HashMap::new();
     let mut sessions: HashMap<String, SessionAgg> = HashMap::new();
 
-    for path in session_paths() {
-        let session_id_from_path = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or_default()
-            .to_string();
+    for (session_id, timestamp) in scan.messages() {
+        let date = timestamp.format("%Y-%m-%d").to_string();
+        *daily_messages.entry(date.clone()).or_insert(0) += 1;
+        daily_sessions
+            .entry(date)
+            .or_default()
+            .insert(session_id.to_string());
 
-        let file = match fs::File::open(&path) {
-            Ok(file) => file,
-            Err(_) => continue,
-        };
-        let reader = BufReader::new(file);
-
-        for (idx, line) in reader.lines().enumerate() {
-            let Ok(line) = line else { continue };
-            let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
-                continue;
-            };
-            let Some(timestamp) = parse_local_timestamp(&value) else {
-                continue;
-            };
-            let timestamp_ms = timestamp.timestamp_millis();
-            let timestamp_iso = timestamp.to_rfc3339();
-            let date = timestamp.format("%Y-%m-%d").to_string();
-            let hour = timestamp.hour();
-            let hour_key = hour.to_string();
-            let session_id = value
-                .get("sessionId")
-                .and_then(|s| s.as_str())
-                .unwrap_or(&session_id_from_path)
-                .to_string();
-            let kind = value.get("type").and_then(|t| t.as_str()).unwrap_or("");
-
-            match kind {
-                "user" => {
-                    let message_id = value
-                        .get("uuid")
-                        .and_then(|u| u.as_str())
-                        .map(ToString::to_string)
-                        .unwrap_or_else(|| format!("{}:{}:user", session_id, idx));
-
-                    if !user_ids_seen.insert(message_id) {
-                        continue;
-                    }
-
-                    *daily_user_messages.entry(date.clone()).or_insert(0) += 1;
-                    daily_sessions
-                        .entry(date)
-                        .or_default()
-                        .insert(session_id.clone());
-                    *hour_counts.entry(hour_key).or_insert(0) += 1;
-                    update_session_agg(&mut sessions, &session_id, timestamp_ms, &timestamp_iso);
-                }
-                "assistant" => {
-                    let message_id = value
-                        .get("message")
-                        .and_then(|m| m.get("id"))
-                        .and_then(|id| id.as_str())
-                        .map(ToString::to_string)
-                        .or_else(|| {
-                            value
-                                .get("uuid")
-                                .and_then(|u| u.as_str())
-                                .map(ToString::to_string)
-                        })
-                        .unwrap_or_else(|| format!("{}:{}:assistant", session_id, idx));
-
-                    let message = value.get("message").unwrap_or(&serde_json::Value::Null);
-                    let usage = message.get("usage").unwrap_or(&serde_json::Value::Null);
-
-                    let entry = assistant_messages.entry(message_id).or_default();
-                    if entry.session_id.is_empty() {
-                        entry.session_id = session_id.clone();
-                    }
-                    if entry.date.is_empty() {
-                        entry.date = date.clone();
-                    }
-                    if entry.timestamp_ms == 0 {
-                        entry.timestamp_ms = timestamp_ms;
-                        entry.timestamp_iso = timestamp_iso.clone();
-                        entry.hour = hour;
-                    }
-
-                    if let Some(model) = message.get("model").and_then(|m| m.as_str()) {
-                        if !model.is_empty() {
-                            entry.model = model.to_string();
-                        }
-                    }
-
-                    entry.input_tokens = entry.input_tokens.max(
-                        usage
-                            .get("input_tokens")
-                            .and_then(|t| t.as_u64())
-                            .unwrap_or(0),
-                    );
-                    entry.output_tokens = entry.output_tokens.max(
-                        usage
-                            .get("output_tokens")
-                            .and_then(|t| t.as_u64())
-                            .unwrap_or(0),
-                    );
-                    entry.cache_read_input_tokens = entry.cache_read_input_tokens.max(
-                        usage
-                            .get("cache_read_input_tokens")
-                            .and_then(|t| t.as_u64())
-                            .unwrap_or(0),
-                    );
-                    entry.cache_creation_input_tokens = entry.cache_creation_input_tokens.max(
-                        usage
-                            .get("cache_creation_input_tokens")
-                            .and_then(|t| t.as_u64())
-                            .unwrap_or(0),
-                    );
-
-                    let direct_ws = usage
-                        .get("web_search_requests")
-                        .and_then(|t| t.as_u64())
-                        .unwrap_or(0);
-                    let server_ws = usage
-                        .get("server_tool_use")
-                        .and_then(|s| s.get("web_search_requests"))
-                        .and_then(|t| t.as_u64())
-                        .unwrap_or(0);
-                    entry.web_search_requests = entry
-                        .web_search_requests
-                        .max(std::cmp::max(direct_ws, server_ws));
-
-                    if let Some(content) = message.get("content").and_then(|c| c.as_array()) {
-                        for block in content {
-                            if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
-                                continue;
-                            }
-                            let signature = block
-                                .get("id")
-                                .and_then(|id| id.as_str())
-                                .map(|id| format!("id:{id}"))
-                                .unwrap_or_else(|| format!("anon:{}", block));
-                            entry.tool_uses.insert(signature);
-                        }
-                    }
-                }
-                _ => {}
-            }
+        let session = sessions.entry(session_id.to_string()).or_default();
+        if session.first.is_none_or(|first| timestamp < first) {
+            session.first = Some(timestamp);
+        }
+        if session.last.is_none_or(|last| timestamp > last) {
+            session.last = Some(timestamp);
         }
+        session.message_count += 1;
     }
 
-    for msg in assistant_messages.values() {
-        *daily_assistant_messages
-            .entry(msg.date.clone())
-            .or_insert(0) += 1;
-        daily_sessions
-            .entry(msg.date.clone())
-            .or_default()
-            .insert(msg.session_id.clone());
-        *daily_tool_calls.entry(msg.date.clone()).or_insert(0) += msg.tool_uses.len() as u64;
-        *hour_counts.entry(msg.hour.to_string()).or_insert(0) += 1;
-        update_session_agg(
-            &mut sessions,
-            &msg.session_id,
-            msg.timestamp_ms,
-            &msg.timestamp_iso,
-        );
+    for msg in &scan.records {
+        let date = msg.timestamp.format("%Y-%m-%d").to_string();
+        *daily_tool_calls.entry(date.clone()).or_insert(0) += msg.tool_uses.len() as u64;
 
         if !msg.model.is_empty() {
-            let model_day = daily_model_tokens.entry(msg.date.clone()).or_default();
+            let model_day = daily_model_tokens.entry(date).or_default();
             *model_day.entry(msg.model.clone()).or_insert(0) += msg.output_tokens;
 
             let usage = model_usage.entry(msg.model.clone()).or_insert(ModelUsage {
@@ -327,9 +189,15 @@ fn compute_stats_cache_from_sessions() -> Result<StatsCache, String> {
         }
     }
 
+    let grid = weekday_hour_grid(scan, TimeseriesMetric::Messages);
+    let hour_counts: HashMap<String, u64> = (0..24)
+        .map(|hour| (hour, grid.iter().map(|day| day[hour]).sum::<f64>() as u64))
+        .filter(|(_, count)| *count > 0)
+        .map(|(hour, count)| (hour.to_string(), count))
+        .collect();
+
     let mut all_dates: HashSet<String> = HashSet::new();
-    all_dates.extend(daily_user_messages.keys().cloned());
-    all_dates.extend(daily_assistant_messages.keys().cloned());
+    all_dates.extend(daily_messages.keys().cloned());
     all_dates.extend(daily_model_tokens.keys().cloned());
 
     let mut sorted_dates: Vec<String> = all_dates.into_iter().collect();
@@ -339,8 +207,7 @@ fn compute_stats_cache_from_sessions() -> Result<StatsCache, String> {
         .iter()
         .map(|date| DailyActivity {
             date: date.clone(),
-            message_count: daily_user_messages.get(date).copied().unwrap_or(0)
-                + daily_assistant_messages.get(date).copied().unwrap_or(0),
+            message_count: daily_messages.get(date).copied().unwrap_or(0),
             session_count: daily_sessions.get(date).map_or(0, |s| s.len() as u64),
             tool_call_count: daily_tool_calls.get(date).copied().unwrap_or(0),
         })
@@ -358,18 +225,12 @@ fn compute_stats_cache_from_sessions() -> Result<StatsCache, String> {
     let mut first_session_date: Option<String> = None;
 
     for (session_id, session) in sessions.iter() {
-        let Some(first_ms) = session.first_ts_ms else {
-            continue;
-        };
-        let Some(last_ms) = session.last_ts_ms else {
+        let (Some(first), Some(last)) = (session.first, session.last) else {
             continue;
         };
 
-        let duration_ms = (last_ms.saturating_sub(first_ms)) as u64;
-        let first_local = DateTime::<Local>::from(
-            std::time::UNIX_EPOCH + std::time::Duration::from_millis(first_ms as u64),
-        );
-        let first_date = first_local.format("%Y-%m-%d").to_string();
+        let duration_ms = (last - first).num_milliseconds().max(0) as u64;
+        let first_date = first.format("%Y-%m-%d").to_string();
 
         if first_session_date
             .as_ref()
@@ -386,41 +247,124 @@ fn compute_stats_cache_from_sessions() -> Result<StatsCache, String> {
                 session_id: session_id.clone(),
                 duration: duration_ms,
                 message_count: session.message_count,
-                timestamp: session.first_ts_iso.clone().unwrap_or_default(),
+                timestamp: first.to_rfc3339(),
             });
         }
     }
 
-    Ok(StatsCache {
+    StatsCache {
         version: 1,
         last_computed_date: Local::now().format("%Y-%m-%d").to_string(),
         daily_activity,
         daily_model_tokens,
         model_usage,
         total_sessions: sessions.len() as u64,
-        total_messages: (user_ids_seen.len() + assistant_messages.len()) as u64,
+        total_messages: scan.messages().count() as u64,
         longest_session,
         first_session_date,
         hour_counts,
         total_speculation_time_saved_ms: 0,
-    })
+    }
+}
+
+/// `metric` summed by local weekday (Monday first) and hour of day. Summed
+/// over the weekdays, the message grid is the stats cache's `hour_counts`.
+pub(crate) fn weekday_hour_grid(
+    scan: &crate::usage::UsageScan,
+    metric: TimeseriesMetric,
+) -> Vec<Vec<f64>> {
+    let mut grid = vec![vec![0.0; 24]; 7];
+    for (at, value) in crate::timeseries::weighted(scan, metric) {
+        grid[at.weekday().num_days_from_monday() as usize][at.hour() as usize] += value;
+    }
+    grid
 }
 
 #[tauri::command]
 pub fn get_history(limit: usize) -> Result<Vec<HistoryEntry>, String> {
-    let path = claude_dir().join("history.jsonl");
-    let file = fs::File::open(&path).map_err(|e| format!("Failed to open history.jsonl: {}", e))?;
-    let reader = BufReader::new(file);
-
-    let mut entries: Vec<HistoryEntry> = reader
-        .lines()
-        .filter_map(|line| line.ok())
-        .filter_map(|line| serde_json::from_str(&line).ok())
-        .collect();
+    crate::history::tail(&claude_dir().join("history.jsonl"), limit)
+}
+
+#[tauri::command]
+pub fn get_history_page(
+    sessions: State<'_, SessionIndex>,
+    cursor: Option<String>,
+    limit: usize,
+) -> Result<HistoryPage, String> {
+    let mut page = crate::history::page(
+        &claude_dir().join("history.jsonl"),
+        cursor.as_deref(),
+        limit,
+    )?;
+    sessions.enrich(&mut page.entries);
+    Ok(page)
+}
 
-    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-    entries.truncate(limit);
-    Ok(entries)
+#[tauri::command]
+pub fn search_history(
+    index: State<'_, HistoryIndex>,
+    sessions: State<'_, SessionIndex>,
+    query: HistorySearchQuery,
+) -> Result<HistoryPage, String> {
+    let mut page = index.search(&claude_dir().join("history.jsonl"), &query)?;
+    sessions.enrich(&mut page.entries);
+    Ok(page)
+}
+
+#[tauri::command]
+pub fn get_transcript(
+    session_id: String,
+    project: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<TranscriptPage, String> {
+    let path = crate::sessions::find_transcript(project.as_deref(), &session_id)
+        .ok_or_else(|| format!("No transcript found for session {session_id}"))?;
+    crate::sessions::transcript(&path, offset.unwrap_or(0), limit)
+}
+
+#[tauri::command]
+pub fn open_transcript(
+    app: AppHandle,
+    session_id: String,
+    project: Option<String>,
+) -> Result<(), String> {
+    let path = crate::sessions::find_transcript(project.as_deref(), &session_id)
+        .ok_or_else(|| format!("No transcript found for session {session_id}"))?;
+    app.opener()
+        .reveal_item_in_dir(&path)
+        .map_err(|e| format!("Failed to open transcript: {e}"))
+}
+
+#[tauri::command]
+pub async fn export_usage(
+    app: AppHandle,
+    request: ExportRequest,
+) -> Result<Option<String>, String> {
+    let records = tauri::async_runtime::spawn_blocking(crate::usage::scan_usage)
+        .await
+        .map_err(|e| format!("Failed to read session usage: {e}"))?;
+    let export = crate::export::build(&records, &request)?;
+
+    let extension = request.format.extension();
+    let Some(path) = app
+        .dialog()
+        .file()
+        .add_filter(extension.to_uppercase(), &[extension])
+        .set_file_name(format!(
+            "claude-usage-{}.{extension}",
+            Local::now().format("%Y-%m-%d")
+        ))
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let path = path
+        .into_path()
+        .map_err(|e| format!("Invalid export path: {e}"))?;
+
+    crate::export::write(&export, request.format, &path)?;
+    Ok(Some(path.display().to_string()))
 }
 
 #[tauri::command]
@@ -460,35 +404,25 @@ pub fn resize_popup(app: AppHandle, height: f64) -> Result<(), String> {
             .set_size(Size::Logical(LogicalSize::new(POPUP_WIDTH, clamped_height)))
             .map_err(|e| format!("Failed to resize popup: {}", e))?;
 
-        if window
-            .move_window_constrained(Position::TrayCenter)
-            .is_err()
-        {
-            let _ = window.move_window(Position::BottomRight);
-        }
-
-        // Snap popup flush against the taskbar
-        #[cfg(target_os = "windows")]
-        {
-            if let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size()) {
-                if let Some(work_bottom) = crate::tray::get_work_area_bottom(pos.x, pos.y) {
-                    let y = work_bottom - size.height as i32;
-                    let _ = window.set_position(tauri::PhysicalPosition::new(pos.x, y));
-                }
-            }
-        }
+        crate::tray::reposition_popup(&window);
     }
 
     Ok(())
 }
 
+#[allow(clippy::needless_return)]
 fn get_oauth_token() -> Result<String, String> {
     #[cfg(target_os = "macos")]
     {
         return get_macos_oauth_token();
     }
 
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        return get_linux_oauth_token();
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         return get_legacy_oauth_token();
     }
@@ -509,6 +443,54 @@ fn get_legacy_oauth_token() -> Result<String, String> {
     Ok(oauth.access_token)
 }
 
+#[cfg(target_os = "linux")]
+fn get_linux_oauth_token() -> Result<String, String> {
+    let file_err = match get_legacy_oauth_token() {
+        Ok(token) => return Ok(token),
+        Err(e) => e,
+    };
+
+    // Claude Code falls back to the Secret Service keyring (GNOME Keyring,
+    // KWallet) when it is available instead of writing .credentials.json
+    let account = std::env::var("USER").unwrap_or_default();
+    let secret = get_secret_service_secret("Claude Code-credentials", Some(&account))
+        .or_else(|_| get_secret_service_secret("Claude Code-credentials", None))
+        .map_err(|e| format!("{file_err}; {e}"))?;
+
+    let creds: Credentials = serde_json::from_str(&secret)
+        .map_err(|e| format!("Failed to parse Secret Service credentials JSON: {e}"))?;
+    let oauth = creds
+        .claude_ai_oauth
+        .ok_or_else(|| "claudeAiOauth missing from Secret Service credentials".to_string())?;
+
+    Ok(oauth.access_token)
+}
+
+#[cfg(target_os = "linux")]
+fn get_secret_service_secret(service: &str, account: Option<&str>) -> Result<String, String> {
+    let mut args = vec!["lookup", "service", service];
+    if let Some(account) = account {
+        args.extend(["account", account]);
+    }
+
+    let output = Command::new("secret-tool")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to run secret-tool (install libsecret-tools): {e}"))?;
+
+    if !output.status.success() || output.stdout.is_empty() {
+        let scope = account
+            .map(|a| format!("account '{a}'"))
+            .unwrap_or_else(|| "any account".to_string());
+        return Err(format!("No Secret Service item for '{service}' ({scope})"));
+    }
+
+    let secret = String::from_utf8(output.stdout)
+        .map_err(|e| format!("Failed to parse Secret Service secret as UTF-8: {e}"))?;
+
+    Ok(secret.trim().to_string())
+}
+
 #[cfg(target_os = "macos")]
 fn get_macos_oauth_token() -> Result<String, String> {
     if let Ok(token) = get_macos_claude_code_oauth_token() {
@@ -649,7 +631,13 @@ fn decrypt_chromium_v10(ciphertext_b64: &str, password: &str) -> Result<String,
 }
 
 #[tauri::command]
-pub async fn get_usage_limits() -> Result<UsageLimits, String> {
+pub async fn get_usage_limits(app: AppHandle) -> Result<UsageLimits, String> {
+    let limits = fetch_usage_limits().await?;
+    crate::tray::update_usage(&app, &limits);
+    Ok(limits)
+}
+
+pub(crate) async fn fetch_usage_limits() -> Result<UsageLimits, String> {
     let token = get_oauth_token()?;
 
     let client = reqwest::Client::new();
@@ -703,11 +691,174 @@ pub fn get_app_version(app: AppHandle) -> String {
     app.package_info().version.to_string()
 }
 
+#[tauri::command]
+pub fn get_settings() -> Settings {
+    crate::settings::load()
+}
+
+#[tauri::command]
+pub fn update_settings(app: AppHandle, settings: Settings) -> Result<Settings, String> {
+    let previous = crate::settings::load();
+    if settings.launch_at_login != previous.launch_at_login {
+        crate::autostart::sync(settings.launch_at_login)?;
+    }
+    crate::metrics::sync(&app, &settings)?;
+    crate::api::sync(&app, &settings)?;
+
+    crate::settings::save(&settings)?;
+    crate::tray::rebuild_menu(&app);
+    if settings.budgets != previous.budgets {
+        crate::budgets::refresh_now(&app);
+    }
+    let _ = app.emit("settings-updated", ());
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn test_webhook(webhook: WebhookConfig) -> Result<(), String> {
+    crate::webhook::send(&webhook, &crate::alerts::test_alert()).await
+}
+
+#[tauri::command]
+pub async fn send_digest(app: AppHandle, period: DigestPeriod) -> Result<Option<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::digest::send(&app, &crate::settings::load(), period)
+    })
+    .await
+    .map_err(|e| format!("Failed to build digest: {e}"))?
+    .map(|path| path.map(|p| p.display().to_string()))
+}
+
+#[tauri::command]
+pub async fn choose_digest_directory(app: AppHandle) -> Result<Option<String>, String> {
+    let Some(path) = app.dialog().file().blocking_pick_folder() else {
+        return Ok(None);
+    };
+    let path = path
+        .into_path()
+        .map_err(|e| format!("Invalid digest directory: {e}"))?;
+    Ok(Some(path.display().to_string()))
+}
+
+#[tauri::command]
+pub async fn get_budget_status(app: AppHandle) -> Result<Vec<BudgetStatus>, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::budgets::statuses(&app))
+        .await
+        .map_err(|e| format!("Failed to evaluate budgets: {e}"))
+}
+
+#[tauri::command]
+pub fn get_extra_usage_history(days: Option<u32>) -> ExtraUsageHistory {
+    crate::extra_usage::history(days.unwrap_or(30).clamp(1, 366))
+}
+
+/// Messages, tokens, tool calls or cost by weekday and hour of day.
+#[tauri::command]
+pub async fn get_weekly_pattern(
+    metric: Option<TimeseriesMetric>,
+    range: Option<TimeRange>,
+) -> Result<WeeklyPattern, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::timeseries::weekly_pattern(
+            metric.unwrap_or(TimeseriesMetric::Messages),
+            &range.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to build activity pattern: {e}"))?
+}
+
+/// Tool calls and error rates per tool, day and project.
+#[tauri::command]
+pub async fn get_tool_usage(range: Option<TimeRange>) -> Result<ToolUsage, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::tools::usage(&range.unwrap_or_default()))
+        .await
+        .map_err(|e| format!("Failed to read tool usage: {e}"))?
+}
+
+/// Calls, result sizes and turn tokens per MCP server.
+#[tauri::command]
+pub async fn get_mcp_usage(range: Option<TimeRange>) -> Result<McpUsage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::tools::mcp_usage(&range.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Failed to read MCP usage: {e}"))?
+}
+
+/// How much usage came from subagents, overall, per session and per day.
+#[tauri::command]
+pub async fn get_subagent_usage(range: Option<TimeRange>) -> Result<SubagentUsage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::subagents::usage(&range.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Failed to read subagent usage: {e}"))?
+}
+
+/// Cache hit ratios, savings and rebuilds per day, project and session.
+#[tauri::command]
+pub async fn get_cache_report(range: Option<TimeRange>) -> Result<CacheReport, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::cache::report(&range.unwrap_or_default()))
+        .await
+        .map_err(|e| format!("Failed to build cache report: {e}"))?
+}
+
+/// Usage per model family and version, the daily mix, in-session model
+/// switches, and Sonnet/Opus usage against their weekly limits.
+#[tauri::command]
+pub async fn get_model_mix(app: AppHandle, range: Option<TimeRange>) -> Result<ModelMix, String> {
+    let limits = crate::tray::latest_limits(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::model_mix::report(&range.unwrap_or_default(), limits.as_ref())
+    })
+    .await
+    .map_err(|e| format!("Failed to build model mix: {e}"))?
+}
+
+/// What used the current five-hour window, rebuilt from local transcripts.
+#[tauri::command]
+pub async fn get_five_hour_window(app: AppHandle) -> Result<FiveHourWindow, String> {
+    let entry = match crate::tray::latest_limits(&app) {
+        Some(limits) => limits.five_hour,
+        None => fetch_usage_limits().await?.five_hour,
+    }
+    .ok_or_else(|| "No five-hour limit on this account".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || crate::five_hour::reconstruct(&entry))
+        .await
+        .map_err(|e| format!("Failed to read session usage: {e}"))
+}
+
+/// Tokens, messages, tool calls or cost per minute, five minutes, hour or
+/// day, e.g. to line activity up with the five-hour limit window.
+#[tauri::command]
+pub async fn get_timeseries(
+    metric: TimeseriesMetric,
+    bucket: TimeseriesBucket,
+    range: Option<TimeRange>,
+) -> Result<Timeseries, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::timeseries::build(metric, bucket, &range.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Failed to build time series: {e}"))?
+}
+
+/// Command line for a Claude Code hook that blocks work over budget.
+#[tauri::command]
+pub fn get_budget_hook_command() -> Result<String, String> {
+    let exe = crate::autostart::executable_path()?;
+    Ok(format!("\"{}\" check-budget", exe.display()))
+}
+
+#[tauri::command]
+pub fn get_api_token_path() -> Option<String> {
+    crate::api::token_path().map(|path| path.display().to_string())
+}
+
 #[tauri::command]
 pub async fn check_for_updates(app: AppHandle) -> Result<UpdateResult, String> {
-    let updater = app
-        .updater()
-        .map_err(|e| format!("Updater init error: {e}"))?;
+    let updater = crate::channel_updater(&app)?;
 
     let update = match updater.check().await {
         Ok(Some(update)) => update,
@@ -715,20 +866,24 @@ pub async fn check_for_updates(app: AppHandle) -> Result<UpdateResult, String> {
             return Ok(UpdateResult {
                 update_available: false,
                 version: None,
+                notes: None,
+                date: None,
             });
         }
         Err(e) => return Err(format!("Update check failed: {e}")),
     };
 
-    let version = update.version.clone();
+    let result = UpdateResult {
+        update_available: true,
+        version: Some(update.version.clone()),
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.date().to_string()),
+    };
 
     // Trigger the install dialog on a background task (non-blocking)
     tauri::async_runtime::spawn(async move {
         crate::prompt_install(update, app).await;
     });
 
-    Ok(UpdateResult {
-        update_available: true,
-        version: Some(version),
-    })
+    Ok(result)
 }
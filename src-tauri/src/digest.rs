@@ -0,0 +1,608 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Timelike};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::models::{
+    Alert, AlertKind, Digest, DigestFormat, DigestPeriod, DigestRow, DigestSession, DigestTotals,
+    Settings, StatsCache, UsageLimits,
+};
+use crate::usage::UsageRecord;
+
+const DIGEST_TICK: Duration = Duration::from_secs(10 * 60);
+const TOP_PROJECTS: usize = 5;
+const PEAKS_FILE: &str = "limit-peaks.json";
+/// Days of limit peaks kept; a weekly digest needs at most the last week
+const PEAK_RETENTION_DAYS: u64 = 31;
+
+/// Highest utilization seen per day (`YYYY-MM-DD`) and limit window
+type LimitPeaks = BTreeMap<String, BTreeMap<String, f64>>;
+
+impl DigestPeriod {
+    /// First and last day of the most recent period that ended before
+    /// `today`: yesterday, or last Monday to Sunday.
+    pub fn last_complete(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            DigestPeriod::Daily => {
+                let yesterday = today - Days::new(1);
+                (yesterday, yesterday)
+            }
+            DigestPeriod::Weekly => {
+                let monday = today - Days::new(u64::from(today.weekday().num_days_from_monday()));
+                (monday - Days::new(7), monday - Days::new(1))
+            }
+        }
+    }
+
+    fn noun(self) -> &'static str {
+        match self {
+            DigestPeriod::Daily => "day",
+            DigestPeriod::Weekly => "week",
+        }
+    }
+
+    fn adjective(self) -> &'static str {
+        match self {
+            DigestPeriod::Daily => "daily",
+            DigestPeriod::Weekly => "weekly",
+        }
+    }
+}
+
+impl DigestFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            DigestFormat::Markdown => "md",
+            DigestFormat::Html => "html",
+        }
+    }
+}
+
+/// Sends each enabled digest once its day or week is over.
+pub fn start_digest_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // Building a digest re-reads every transcript
+            let handle = app.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || send_due_digests(&handle)).await;
+            tokio::time::sleep(DIGEST_TICK).await;
+        }
+    });
+}
+
+fn send_due_digests(app: &AppHandle) {
+    let today = Local::now().date_naive();
+
+    for period in [DigestPeriod::Daily, DigestPeriod::Weekly] {
+        let mut settings = crate::settings::load();
+        let (start, _) = period.last_complete(today);
+        let start_key = start.format("%Y-%m-%d").to_string();
+        let (enabled, last_sent) = match period {
+            DigestPeriod::Daily => (settings.digest_daily, &mut settings.last_daily_digest),
+            DigestPeriod::Weekly => (settings.digest_weekly, &mut settings.last_weekly_digest),
+        };
+        if !enabled || last_sent.as_deref() == Some(start_key.as_str()) {
+            continue;
+        }
+
+        // Recorded before sending so a failing digest isn't retried every tick
+        *last_sent = Some(start_key);
+        if let Err(e) = crate::settings::save(&settings) {
+            eprintln!("Failed to save settings: {e}");
+            continue;
+        }
+        let _ = app.emit("settings-updated", ());
+
+        if let Err(e) = send(app, &settings, period) {
+            eprintln!("{} digest error: {e}", period.adjective());
+        }
+    }
+}
+
+/// Builds the digest for the last complete period and delivers it as a
+/// notification, a file in the digest directory and, if enabled, to the
+/// webhooks. Returns the path of the written file.
+pub fn send(
+    app: &AppHandle,
+    settings: &Settings,
+    period: DigestPeriod,
+) -> Result<Option<PathBuf>, String> {
+    let (start, end) = period.last_complete(Local::now().date_naive());
+    let digest = build(period, start, end)?;
+
+    let alert = Alert {
+        kind: AlertKind::Digest,
+        title: title(&digest),
+        message: headline(&digest),
+        window: None,
+        utilization: None,
+        resets_at: None,
+        summary: None,
+        digest: Some(digest),
+        budget: None,
+        timestamp: Local::now().to_rfc3339(),
+    };
+    crate::alerts::notify(app, settings, &alert);
+    if settings.digest_webhooks {
+        crate::webhook::dispatch(&settings.webhooks, &alert);
+    }
+
+    let (Some(directory), Some(digest)) = (&settings.digest_directory, &alert.digest) else {
+        return Ok(None);
+    };
+    write(digest, settings.digest_format, Path::new(directory)).map(Some)
+}
+
+/// Summarizes the days from `start` to `end` (inclusive) and the same number
+/// of days before them.
+pub fn build(period: DigestPeriod, start: NaiveDate, end: NaiveDate) -> Result<Digest, String> {
+    let stats = crate::commands::get_stats_cache()?;
+    let records = crate::usage::scan_usage();
+
+    let days = (end - start).num_days().max(0) as u64 + 1;
+    let previous_start = start - Days::new(days);
+    let previous_end = start - Days::new(1);
+    let in_range = |record: &&UsageRecord, from: NaiveDate, to: NaiveDate| {
+        (from..=to).contains(&record.timestamp.date_naive())
+    };
+    let current: Vec<&UsageRecord> = records.iter().filter(|r| in_range(r, start, end)).collect();
+    let previous: Vec<&UsageRecord> = records
+        .iter()
+        .filter(|r| in_range(r, previous_start, previous_end))
+        .collect();
+
+    let mut models: HashMap<&str, DigestRow> = HashMap::new();
+    let mut projects: HashMap<&str, DigestRow> = HashMap::new();
+    let mut sessions: HashMap<&str, (&UsageRecord, DateTime<Local>, u64)> = HashMap::new();
+    let mut hours = [0u64; 24];
+
+    for record in &current {
+        let project = crate::history::project_name(&record.project);
+        for (rows, name) in [
+            (&mut models, record.model.as_str()),
+            (&mut projects, project),
+        ] {
+            if name.is_empty() {
+                continue;
+            }
+            let row = rows.entry(name).or_insert_with(|| DigestRow {
+                name: name.to_string(),
+                total_tokens: 0,
+                cost_usd: 0.0,
+            });
+            row.total_tokens += record.total_tokens();
+            row.cost_usd += record.cost_usd();
+        }
+
+        // Records are oldest first, so the first one seen starts the session
+        let session =
+            sessions
+                .entry(record.session_id.as_str())
+                .or_insert((record, record.timestamp, 0));
+        session.1 = record.timestamp;
+        session.2 += 1;
+
+        hours[record.timestamp.hour() as usize] += 1;
+    }
+
+    let longest_session = sessions
+        .into_values()
+        .max_by_key(|(first, last, _)| *last - first.timestamp)
+        .map(|(first, last, messages)| DigestSession {
+            session_id: first.session_id.clone(),
+            project: crate::history::project_name(&first.project).to_string(),
+            duration_ms: (last - first.timestamp).num_milliseconds().max(0) as u64,
+            messages,
+        });
+    let busiest_hour = (0..24u32)
+        .filter(|hour| hours[*hour as usize] > 0)
+        .max_by_key(|hour| hours[*hour as usize]);
+
+    let mut limit_peaks: BTreeMap<String, f64> = BTreeMap::new();
+    for (_, day) in load_peaks().range(format_date(start)..=format_date(end)) {
+        for (window, utilization) in day {
+            let peak = limit_peaks.entry(window.clone()).or_insert(0.0);
+            *peak = peak.max(*utilization);
+        }
+    }
+
+    Ok(Digest {
+        period,
+        start: format_date(start),
+        end: format_date(end),
+        totals: totals(&current, &stats, start, end),
+        previous: totals(&previous, &stats, previous_start, previous_end),
+        models: by_cost(models.into_values().collect(), usize::MAX),
+        top_projects: by_cost(projects.into_values().collect(), TOP_PROJECTS),
+        longest_session,
+        busiest_hour,
+        limit_peaks,
+        generated_at: Local::now().to_rfc3339(),
+    })
+}
+
+/// Token and cost totals come from the transcripts; message and tool call
+/// counts from the stats cache's daily activity.
+fn totals(
+    records: &[&UsageRecord],
+    stats: &StatsCache,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> DigestTotals {
+    let (from, to) = (format_date(from), format_date(to));
+    let activity = stats
+        .daily_activity
+        .iter()
+        .filter(|a| a.date >= from && a.date <= to);
+
+    DigestTotals {
+        total_tokens: records.iter().map(|r| r.total_tokens()).sum(),
+        // Summing no floats gives -0.0, which would render as `$-0.00`
+        cost_usd: records.iter().fold(0.0, |sum, r| sum + r.cost_usd()),
+        messages: activity.clone().map(|a| a.message_count).sum(),
+        sessions: records
+            .iter()
+            .map(|r| r.session_id.as_str())
+            .collect::<HashSet<_>>()
+            .len() as u64,
+        tool_calls: activity.map(|a| a.tool_call_count).sum(),
+    }
+}
+
+fn by_cost(mut rows: Vec<DigestRow>, limit: usize) -> Vec<DigestRow> {
+    rows.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+    rows.truncate(limit);
+    rows
+}
+
+/// Held while the limit peaks file is read, updated and written back; the
+/// poller, manual refreshes and the frontend all record peaks.
+#[derive(Default)]
+pub struct DigestState {
+    peaks_file: Mutex<()>,
+}
+
+/// Remembers the day's highest utilization of each limit window, since the
+/// usage endpoint only reports the current value.
+pub fn record_limit_peaks(app: &AppHandle, limits: &UsageLimits) {
+    let state = app.state::<DigestState>();
+    let _guard = state.peaks_file.lock().unwrap();
+    let today = Local::now().date_naive();
+    let mut peaks = load_peaks();
+    let day = peaks.entry(format_date(today)).or_default();
+
+    let extra_usage = limits
+        .extra_usage
+        .as_ref()
+        .filter(|extra| extra.is_enabled)
+        .and_then(|extra| extra.utilization)
+        .map(|utilization| ("extra_usage", utilization));
+    let windows = crate::tray::limit_windows(limits)
+        .map(|(window, _, entry)| (window, entry.utilization))
+        .chain(extra_usage);
+
+    let mut changed = false;
+    for (window, utilization) in windows {
+        if day.get(window).is_none_or(|peak| utilization > *peak) {
+            day.insert(window.to_string(), utilization);
+            changed = true;
+        }
+    }
+    if !changed {
+        return;
+    }
+
+    let cutoff = format_date(today - Days::new(PEAK_RETENTION_DAYS));
+    peaks.retain(|date, _| *date >= cutoff);
+    if let Err(e) = save_peaks(&peaks) {
+        eprintln!("limit peaks error: {e}");
+    }
+}
+
+fn peaks_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("com.brewstatus").join(PEAKS_FILE))
+}
+
+fn load_peaks() -> LimitPeaks {
+    peaks_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_peaks(peaks: &LimitPeaks) -> Result<(), String> {
+    let path = peaks_path().ok_or_else(|| "Could not find data directory".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let data = serde_json::to_string(peaks)
+        .map_err(|e| format!("Failed to serialize limit peaks: {e}"))?;
+    // Renamed into place so readers never see a half-written file, which
+    // would load as empty and lose the history on the next save
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, data).map_err(|e| format!("Failed to write {}: {e}", temp.display()))?;
+    fs::rename(&temp, &path).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Writes the rendered digest into `directory`, named after its period.
+pub fn write(digest: &Digest, format: DigestFormat, directory: &Path) -> Result<PathBuf, String> {
+    fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create {}: {e}", directory.display()))?;
+    let path = directory.join(format!(
+        "claude-{}-digest-{}.{}",
+        digest.period.adjective(),
+        digest.start,
+        format.extension()
+    ));
+    fs::write(&path, render(digest, format))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Format-neutral pieces of a digest report.
+enum Block {
+    Heading(String),
+    Paragraph(String),
+    Table(Vec<String>, Vec<Vec<String>>),
+    List(Vec<String>),
+}
+
+pub fn render(digest: &Digest, format: DigestFormat) -> String {
+    let title = title(digest);
+    let blocks = report(digest);
+    match format {
+        DigestFormat::Markdown => render_markdown(&title, &blocks),
+        DigestFormat::Html => render_html(&title, &blocks),
+    }
+}
+
+fn title(digest: &Digest) -> String {
+    match digest.period {
+        DigestPeriod::Daily => format!("Claude Code daily digest: {}", digest.start),
+        DigestPeriod::Weekly => format!(
+            "Claude Code weekly digest: {} to {}",
+            digest.start, digest.end
+        ),
+    }
+}
+
+/// One-line summary used as the notification body.
+fn headline(digest: &Digest) -> String {
+    let totals = &digest.totals;
+    format!(
+        "{} tokens (~{}, {} vs previous {}), {} sessions, {} messages.",
+        crate::tray::format_token_count(totals.total_tokens),
+        format_cost(totals.cost_usd),
+        change(totals.cost_usd, digest.previous.cost_usd),
+        digest.period.noun(),
+        totals.sessions,
+        totals.messages
+    )
+}
+
+fn report(digest: &Digest) -> Vec<Block> {
+    let (current, previous) = (&digest.totals, &digest.previous);
+    let noun = digest.period.noun();
+    let mut blocks = vec![Block::Table(
+        headers(&["", "This period", &format!("Previous {noun}"), "Change"]),
+        vec![
+            vec![
+                "Tokens".to_string(),
+                crate::tray::format_token_count(current.total_tokens),
+                crate::tray::format_token_count(previous.total_tokens),
+                change(current.total_tokens as f64, previous.total_tokens as f64),
+            ],
+            vec![
+                "Estimated cost".to_string(),
+                format_cost(current.cost_usd),
+                format_cost(previous.cost_usd),
+                change(current.cost_usd, previous.cost_usd),
+            ],
+            vec![
+                "Sessions".to_string(),
+                current.sessions.to_string(),
+                previous.sessions.to_string(),
+                change(current.sessions as f64, previous.sessions as f64),
+            ],
+            vec![
+                "Messages".to_string(),
+                current.messages.to_string(),
+                previous.messages.to_string(),
+                change(current.messages as f64, previous.messages as f64),
+            ],
+            vec![
+                "Tool calls".to_string(),
+                current.tool_calls.to_string(),
+                previous.tool_calls.to_string(),
+                change(current.tool_calls as f64, previous.tool_calls as f64),
+            ],
+        ],
+    )];
+
+    let rows = |rows: &[DigestRow]| {
+        rows.iter()
+            .map(|row| {
+                vec![
+                    row.name.clone(),
+                    crate::tray::format_token_count(row.total_tokens),
+                    format_cost(row.cost_usd),
+                ]
+            })
+            .collect()
+    };
+    if !digest.models.is_empty() {
+        blocks.push(Block::Heading("Tokens by model".to_string()));
+        blocks.push(Block::Table(
+            headers(&["Model", "Tokens", "Estimated cost"]),
+            rows(&digest.models),
+        ));
+    }
+    if !digest.top_projects.is_empty() {
+        blocks.push(Block::Heading("Top projects".to_string()));
+        blocks.push(Block::Table(
+            headers(&["Project", "Tokens", "Estimated cost"]),
+            rows(&digest.top_projects),
+        ));
+    }
+
+    let mut highlights = Vec::new();
+    if let Some(session) = &digest.longest_session {
+        highlights.push(format!(
+            "Longest session: {} in {} ({} replies)",
+            format_duration(session.duration_ms),
+            session.project,
+            session.messages
+        ));
+    }
+    if let Some(hour) = digest.busiest_hour {
+        highlights.push(format!(
+            "Busiest hour: {hour:02}:00 to {:02}:00",
+            (hour + 1) % 24
+        ));
+    }
+    if !highlights.is_empty() {
+        blocks.push(Block::Heading("Highlights".to_string()));
+        blocks.push(Block::List(highlights));
+    }
+
+    blocks.push(Block::Heading("Limit peaks".to_string()));
+    if digest.limit_peaks.is_empty() {
+        blocks.push(Block::Paragraph(format!(
+            "No limit usage was recorded this {noun}; Brew Status records peaks while it's running."
+        )));
+    } else {
+        blocks.push(Block::Table(
+            headers(&["Limit", "Peak utilization"]),
+            digest
+                .limit_peaks
+                .iter()
+                .map(|(window, peak)| {
+                    vec![
+                        crate::tray::limit_label(window).to_string(),
+                        format!("{}%", peak.round()),
+                    ]
+                })
+                .collect(),
+        ));
+    }
+
+    blocks
+}
+
+fn headers(names: &[&str]) -> Vec<String> {
+    names.iter().map(ToString::to_string).collect()
+}
+
+fn render_markdown(title: &str, blocks: &[Block]) -> String {
+    let cell = |text: &str| text.replace('|', "\\|");
+    let mut out = format!("# {title}\n");
+
+    for block in blocks {
+        out.push('\n');
+        match block {
+            Block::Heading(text) => {
+                let _ = writeln!(out, "## {text}");
+            }
+            Block::Paragraph(text) => {
+                let _ = writeln!(out, "{text}");
+            }
+            Block::Table(headers, rows) => {
+                let _ = writeln!(out, "| {} |", headers.join(" | "));
+                let _ = writeln!(out, "|{}", "---|".repeat(headers.len()));
+                for row in rows {
+                    let cells: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                    let _ = writeln!(out, "| {} |", cells.join(" | "));
+                }
+            }
+            Block::List(items) => {
+                for item in items {
+                    let _ = writeln!(out, "- {item}");
+                }
+            }
+        }
+    }
+    out
+}
+
+fn render_html(title: &str, blocks: &[Block]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+         <style>body{{font-family:system-ui,sans-serif;max-width:720px;margin:2em auto}}\
+         table{{border-collapse:collapse}}th,td{{padding:4px 12px;border-bottom:1px solid #ddd;text-align:left}}</style>\n\
+         </head>\n<body>\n<h1>{0}</h1>\n",
+        escape_html(title)
+    );
+
+    for block in blocks {
+        match block {
+            Block::Heading(text) => {
+                let _ = writeln!(out, "<h2>{}</h2>", escape_html(text));
+            }
+            Block::Paragraph(text) => {
+                let _ = writeln!(out, "<p>{}</p>", escape_html(text));
+            }
+            Block::Table(headers, rows) => {
+                out.push_str("<table>\n<tr>");
+                for header in headers {
+                    let _ = write!(out, "<th>{}</th>", escape_html(header));
+                }
+                out.push_str("</tr>\n");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        let _ = write!(out, "<td>{}</td>", escape_html(cell));
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</table>\n");
+            }
+            Block::List(items) => {
+                out.push_str("<ul>\n");
+                for item in items {
+                    let _ = writeln!(out, "<li>{}</li>", escape_html(item));
+                }
+                out.push_str("</ul>\n");
+            }
+        }
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn change(current: f64, previous: f64) -> String {
+    if previous == 0.0 {
+        return if current == 0.0 { "±0%" } else { "new" }.to_string();
+    }
+    format!("{:+.0}%", (current - previous) / previous * 100.0)
+}
+
+fn format_cost(cost: f64) -> String {
+    format!("${cost:.2}")
+}
+
+fn format_duration(ms: u64) -> String {
+    let minutes = ms / 60_000;
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{Local, NaiveDate};
+
+use crate::models::{ExportFormat, ExportRequest, UsageExport, UsageRow};
+use crate::usage::UsageRecord;
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl UsageRow {
+    fn add(&mut self, record: &UsageRecord) {
+        let timestamp = record.timestamp.to_rfc3339();
+        if self.first_seen.is_empty() {
+            self.first_seen = timestamp.clone();
+        }
+        self.last_seen = timestamp;
+        self.messages += 1;
+        self.input_tokens += record.input_tokens;
+        self.output_tokens += record.output_tokens;
+        self.cache_read_input_tokens += record.cache_read_input_tokens;
+        self.cache_creation_input_tokens += record.cache_creation_input_tokens;
+        self.total_tokens += record.total_tokens();
+        self.cost_usd += record.cost_usd();
+    }
+}
+
+/// Groups usage records (oldest first) into the export tables for the
+/// requested date range.
+pub fn build(records: &[UsageRecord], request: &ExportRequest) -> Result<UsageExport, String> {
+    let from = parse_date(request.from.as_deref())?;
+    let to = parse_date(request.to.as_deref())?;
+
+    let mut daily: BTreeMap<String, UsageRow> = BTreeMap::new();
+    let mut models: BTreeMap<String, UsageRow> = BTreeMap::new();
+    let mut projects: BTreeMap<String, UsageRow> = BTreeMap::new();
+    let mut sessions: BTreeMap<String, UsageRow> = BTreeMap::new();
+
+    for record in records {
+        let date = record.timestamp.date_naive();
+        if from.is_some_and(|from| date < from) || to.is_some_and(|to| date > to) {
+            continue;
+        }
+
+        let rows = [
+            (&mut daily, date.format("%Y-%m-%d").to_string(), ""),
+            (&mut models, record.model.clone(), ""),
+            (&mut projects, record.project.clone(), ""),
+            (
+                &mut sessions,
+                record.session_id.clone(),
+                record.project.as_str(),
+            ),
+        ];
+        for (table, key, label) in rows {
+            if key.is_empty() {
+                continue;
+            }
+            table
+                .entry(key.clone())
+                .or_insert_with(|| UsageRow {
+                    key,
+                    label: label.to_string(),
+                    ..Default::default()
+                })
+                .add(record);
+        }
+    }
+
+    let by_cost = |table: BTreeMap<String, UsageRow>| {
+        let mut rows: Vec<UsageRow> = table.into_values().collect();
+        rows.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+        rows
+    };
+
+    Ok(UsageExport {
+        generated_at: Local::now().to_rfc3339(),
+        from: request.from.clone(),
+        to: request.to.clone(),
+        daily: daily.into_values().collect(),
+        models: by_cost(models),
+        projects: by_cost(projects),
+        sessions: by_cost(sessions),
+    })
+}
+
+pub fn write(export: &UsageExport, format: ExportFormat, path: &Path) -> Result<(), String> {
+    let contents = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(export)
+            .map_err(|e| format!("Failed to serialize export: {e}"))?,
+        ExportFormat::Csv => to_csv(export),
+    };
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// A single long-format table; the `section` column tells the breakdowns
+/// apart so the file can be filtered or pivoted in a spreadsheet.
+fn to_csv(export: &UsageExport) -> String {
+    let mut csv = String::from(
+        "section,key,label,first_seen,last_seen,messages,input_tokens,output_tokens,\
+         cache_read_input_tokens,cache_creation_input_tokens,total_tokens,cost_usd\n",
+    );
+
+    let sections = [
+        ("daily", &export.daily),
+        ("model", &export.models),
+        ("project", &export.projects),
+        ("session", &export.sessions),
+    ];
+    for (section, rows) in sections {
+        for row in rows {
+            let fields = [
+                section.to_string(),
+                csv_field(&row.key),
+                csv_field(&row.label),
+                row.first_seen.clone(),
+                row.last_seen.clone(),
+                row.messages.to_string(),
+                row.input_tokens.to_string(),
+                row.output_tokens.to_string(),
+                row.cache_read_input_tokens.to_string(),
+                row.cache_creation_input_tokens.to_string(),
+                row.total_tokens.to_string(),
+                format!("{:.4}", row.cost_usd),
+            ];
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn parse_date(value: Option<&str>) -> Result<Option<NaiveDate>, String> {
+    value
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            NaiveDate::parse_from_str(v, "%Y-%m-%d").map_err(|_| format!("Invalid date '{v}'"))
+        })
+        .transpose()
+}
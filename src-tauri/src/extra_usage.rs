@@ -0,0 +1,242 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, TimeDelta, TimeZone};
+use tauri::{AppHandle, Manager};
+
+use crate::models::{
+    Alert, AlertKind, ExtraUsage, ExtraUsageDay, ExtraUsageHistory, ExtraUsageProjection,
+    ExtraUsageSample,
+};
+
+const SAMPLES_FILE: &str = "extra-usage.json";
+/// A bit over a year of samples
+const RETENTION_DAYS: u64 = 400;
+/// Credits that start moving again after this long idle mean the plan limits
+/// ran out again, rather than the same stretch of overage continuing
+const OVERAGE_QUIET_PERIOD: TimeDelta = TimeDelta::hours(5);
+/// Too little of the cycle to extrapolate from before this
+const MIN_PROJECTION_ELAPSED: TimeDelta = TimeDelta::hours(12);
+
+/// A sample with its parsed timestamp
+type TimedSample<'a> = (DateTime<Local>, &'a ExtraUsageSample);
+
+/// Held while the samples file is read, updated and written back, so
+/// overlapping limit polls can't drop each other's samples.
+#[derive(Default)]
+pub struct ExtraUsageState {
+    samples_file: Mutex<()>,
+}
+
+/// Stores a sample whenever the credits used change, and alerts when credits
+/// start being consumed because the plan limits are used up.
+pub fn record(app: &AppHandle, extra: &ExtraUsage) {
+    let Some(used) = extra.used_credits.filter(|_| extra.is_enabled) else {
+        return;
+    };
+    let state = app.state::<ExtraUsageState>();
+    let _guard = state.samples_file.lock().unwrap();
+    let now = Local::now();
+    let mut samples = load_samples();
+
+    if let Some(last) = samples.last() {
+        if last.used_credits == used && last.monthly_limit == extra.monthly_limit {
+            return;
+        }
+        let quiet = last_increase(&samples).is_none_or(|at| now - at >= OVERAGE_QUIET_PERIOD);
+        if used > last.used_credits && quiet {
+            alert(app, extra, used);
+        }
+    }
+
+    samples.push(ExtraUsageSample {
+        timestamp: now.to_rfc3339(),
+        used_credits: used,
+        monthly_limit: extra.monthly_limit,
+        utilization: extra.utilization,
+    });
+    let cutoff = now - Days::new(RETENTION_DAYS);
+    samples.retain(|s| parse_timestamp(&s.timestamp).is_some_and(|at| at >= cutoff));
+    if let Err(e) = save_samples(&samples) {
+        eprintln!("extra usage history error: {e}");
+    }
+}
+
+fn alert(app: &AppHandle, extra: &ExtraUsage, used: f64) {
+    let of_limit = extra
+        .monthly_limit
+        .map(|limit| format!(" of {}", format_credits(limit)))
+        .unwrap_or_default();
+    let settings = crate::settings::load();
+    crate::alerts::deliver(
+        app,
+        &settings,
+        Alert {
+            kind: AlertKind::ExtraUsage,
+            title: "Extra usage started".to_string(),
+            message: format!(
+                "Your plan limits are used up, so extra usage credits are being spent ({}{of_limit} used this month).",
+                format_credits(used)
+            ),
+            window: Some("extra_usage".to_string()),
+            utilization: extra.utilization,
+            resets_at: None,
+            summary: None,
+            digest: None,
+            budget: None,
+            timestamp: Local::now().to_rfc3339(),
+        },
+    );
+}
+
+/// Credits used per day over the last `days` days, the raw samples in that
+/// range, and the projected spend for the current billing cycle.
+pub fn history(days: u32) -> ExtraUsageHistory {
+    let samples = load_samples();
+    let timed: Vec<TimedSample> = samples
+        .iter()
+        .filter_map(|s| Some((parse_timestamp(&s.timestamp)?, s)))
+        .collect();
+    let now = Local::now();
+    let first_day = now.date_naive() - Days::new(u64::from(days.max(1)) - 1);
+
+    let mut daily = Vec::new();
+    let mut pending = timed.iter().peekable();
+    let mut used: Option<f64> = None;
+    for offset in 0..u64::from(days.max(1)) {
+        let date = first_day + Days::new(offset);
+        let mut spent = 0.0;
+        while let Some((at, sample)) = pending.next_if(|(at, _)| at.date_naive() <= date) {
+            if let (Some(previous), true) = (used, at.date_naive() == date) {
+                // A drop means the cycle reset; everything since was spent today
+                spent += if sample.used_credits >= previous {
+                    sample.used_credits - previous
+                } else {
+                    sample.used_credits
+                };
+            }
+            used = Some(sample.used_credits);
+        }
+        if let Some(used) = used {
+            daily.push(ExtraUsageDay {
+                date: date.format("%Y-%m-%d").to_string(),
+                used_credits: used,
+                spent,
+            });
+        }
+    }
+
+    ExtraUsageHistory {
+        projection: projection(&timed, now),
+        samples: timed
+            .iter()
+            .filter(|(at, _)| at.date_naive() >= first_day)
+            .map(|(_, sample)| (*sample).clone())
+            .collect(),
+        daily,
+    }
+}
+
+/// Extrapolates the credits used so far this cycle to the end of it.
+fn projection(samples: &[TimedSample], now: DateTime<Local>) -> Option<ExtraUsageProjection> {
+    let (last_at, last) = samples.last()?;
+    let (start, end) = billing_cycle(samples, now)?;
+    // Nothing seen this cycle yet, so the last value belongs to an earlier one
+    if *last_at < start {
+        return None;
+    }
+
+    let elapsed = (now - start).num_seconds() as f64;
+    let length = (end - start).num_seconds() as f64;
+    let projected = if now - start < MIN_PROJECTION_ELAPSED {
+        last.used_credits
+    } else {
+        last.used_credits * length / elapsed
+    };
+
+    Some(ExtraUsageProjection {
+        cycle_start: start.to_rfc3339(),
+        cycle_end: end.to_rfc3339(),
+        used_credits: last.used_credits,
+        projected_credits: projected,
+        monthly_limit: last.monthly_limit,
+        projected_utilization: last
+            .monthly_limit
+            .filter(|limit| *limit > 0.0)
+            .map(|limit| projected / limit * 100.0),
+    })
+}
+
+/// Credits reset monthly on the billing date. The last reset seen in the
+/// samples anchors the cycle; without one, calendar months are assumed.
+fn billing_cycle(
+    samples: &[TimedSample],
+    now: DateTime<Local>,
+) -> Option<(DateTime<Local>, DateTime<Local>)> {
+    let last_reset = samples
+        .windows(2)
+        .rev()
+        .find(|w| w[1].1.used_credits < w[0].1.used_credits)
+        .map(|w| w[1].0);
+    let mut start = match last_reset {
+        Some(at) => at,
+        None => local_midnight(now.date_naive().with_day(1)?)?,
+    };
+    let mut end = start.checked_add_months(Months::new(1))?;
+    while end <= now {
+        start = end;
+        end = start.checked_add_months(Months::new(1))?;
+    }
+    Some((start, end))
+}
+
+fn last_increase(samples: &[ExtraUsageSample]) -> Option<DateTime<Local>> {
+    samples
+        .windows(2)
+        .rev()
+        .find(|w| w[1].used_credits > w[0].used_credits)
+        .and_then(|w| parse_timestamp(&w[1].timestamp))
+}
+
+fn format_credits(credits: f64) -> String {
+    if credits.fract() == 0.0 {
+        format!("{credits:.0}")
+    } else {
+        format!("{credits:.2}")
+    }
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|at| at.with_timezone(&Local))
+}
+
+fn local_midnight(date: NaiveDate) -> Option<DateTime<Local>> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+}
+
+fn samples_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("com.brewstatus").join(SAMPLES_FILE))
+}
+
+fn load_samples() -> Vec<ExtraUsageSample> {
+    samples_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_samples(samples: &[ExtraUsageSample]) -> Result<(), String> {
+    let path = samples_path().ok_or_else(|| "Could not find data directory".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let data = serde_json::to_string(samples)
+        .map_err(|e| format!("Failed to serialize extra usage history: {e}"))?;
+    fs::write(&path, data).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
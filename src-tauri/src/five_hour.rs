@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::models::{FiveHourWindow, LimitEntry, UsageShare};
+use crate::usage::{by_tokens, UsageRecord};
+
+const WINDOW_LENGTH: TimeDelta = TimeDelta::hours(5);
+/// Below this the server's rounding swamps the tokens-per-percent estimate
+const MIN_ESTIMATE_UTILIZATION: f64 = 2.0;
+
+/// Rebuilds the window behind the server's `five_hour` entry from the local
+/// transcripts. Without `resets_at` no window is running, so the last five
+/// hours are shown instead.
+///
+/// Use on other machines or claude.ai counts towards the utilization but
+/// isn't in the transcripts, which makes the tokens-per-percent estimate low.
+pub fn reconstruct(entry: &LimitEntry) -> FiveHourWindow {
+    let resets_at = entry
+        .resets_at
+        .as_deref()
+        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+        .map(|at| at.with_timezone(&Local));
+    let start = resets_at.unwrap_or_else(Local::now) - WINDOW_LENGTH;
+
+    let scan = crate::usage::scan(Some(start));
+    let total_tokens: u64 = scan.records.iter().map(UsageRecord::total_tokens).sum();
+    let mut models: HashMap<String, UsageShare> = HashMap::new();
+    let mut projects: HashMap<String, UsageShare> = HashMap::new();
+    for record in &scan.records {
+        let project = crate::history::project_name(&record.project);
+        if !project.is_empty() {
+            projects
+                .entry(project.to_string())
+                .or_insert_with(|| UsageShare::new(project))
+                .add(record);
+        }
+        // `<synthetic>` and other non-Claude ids carry no real usage
+        if crate::pricing::price_for(&record.model).is_some() {
+            models
+                .entry(record.model.clone())
+                .or_insert_with(|| UsageShare::new(&record.model))
+                .add(record);
+        }
+    }
+
+    let tokens_per_percent = tokens_per_percent(total_tokens, entry.utilization);
+    FiveHourWindow {
+        start: start.to_rfc3339(),
+        resets_at: resets_at.map(|at| at.to_rfc3339()),
+        utilization: entry.utilization,
+        messages: scan.messages().count() as u64,
+        total_tokens,
+        cost_usd: scan.records.iter().fold(0.0, |sum, r| sum + r.cost_usd()),
+        models: by_tokens(models, total_tokens),
+        projects: by_tokens(projects, total_tokens),
+        tokens_per_percent,
+        remaining_tokens: tokens_per_percent
+            .map(|rate| (rate * (100.0 - entry.utilization)).max(0.0) as u64),
+    }
+}
+
+/// Local tokens behind each percent of a limit's utilization, or `None` while
+/// it is below [`MIN_ESTIMATE_UTILIZATION`].
+pub fn tokens_per_percent(total_tokens: u64, utilization: f64) -> Option<f64> {
+    (utilization >= MIN_ESTIMATE_UTILIZATION).then(|| total_tokens as f64 / utilization)
+}
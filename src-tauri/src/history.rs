@@ -0,0 +1,392 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::models::{HistoryEntry, HistoryPage, HistorySearchQuery};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+const TAIL_BLOCK_SIZE: u64 = 64 * 1024;
+/// Extra lines read past the requested count to detect out-of-order timestamps
+const TAIL_OVERSCAN: usize = 32;
+/// Leading bytes kept to notice the file being replaced by one at least as long
+const HEAD_LEN: usize = 4096;
+
+/// In-memory index over `history.jsonl`. The file is append-only, so each
+/// refresh only parses the bytes written since the previous one; entries are
+/// kept ordered by `(timestamp, line)` so date ranges and cursors are binary
+/// searches rather than scans of the whole file.
+#[derive(Default)]
+pub struct HistoryIndex {
+    inner: Mutex<IndexData>,
+}
+
+#[derive(Default)]
+struct IndexData {
+    indexed_len: u64,
+    /// First bytes of the indexed part of the file, up to `HEAD_LEN`
+    head: Vec<u8>,
+    next_line: u64,
+    entries: Vec<IndexedEntry>,
+    /// Positions into `entries`, sorted by `(timestamp, line)` ascending
+    order: Vec<usize>,
+}
+
+struct IndexedEntry {
+    entry: HistoryEntry,
+    line: u64,
+    display_lower: String,
+}
+
+impl IndexedEntry {
+    fn key(&self) -> (u64, u64) {
+        (self.entry.timestamp, self.line)
+    }
+}
+
+enum Matcher {
+    All,
+    Substring {
+        needle: String,
+        case_sensitive: bool,
+    },
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(query: &HistorySearchQuery) -> Result<Self, String> {
+        let text = query.text.as_deref().unwrap_or_default();
+        if text.is_empty() {
+            return Ok(Matcher::All);
+        }
+
+        if query.regex {
+            let regex = RegexBuilder::new(text)
+                .case_insensitive(!query.case_sensitive)
+                .build()
+                .map_err(|e| format!("Invalid search pattern: {e}"))?;
+            return Ok(Matcher::Regex(regex));
+        }
+
+        Ok(Matcher::Substring {
+            needle: if query.case_sensitive {
+                text.to_string()
+            } else {
+                text.to_lowercase()
+            },
+            case_sensitive: query.case_sensitive,
+        })
+    }
+
+    fn matches(&self, indexed: &IndexedEntry) -> bool {
+        match self {
+            Matcher::All => true,
+            Matcher::Substring {
+                needle,
+                case_sensitive: true,
+            } => indexed.entry.display.contains(needle.as_str()),
+            Matcher::Substring { needle, .. } => indexed.display_lower.contains(needle.as_str()),
+            Matcher::Regex(regex) => regex.is_match(&indexed.entry.display),
+        }
+    }
+}
+
+impl HistoryIndex {
+    pub fn search(&self, path: &Path, query: &HistorySearchQuery) -> Result<HistoryPage, String> {
+        let matcher = Matcher::new(query)?;
+        let cursor = query.cursor.as_deref().map(parse_cursor).transpose()?;
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+
+        let mut data = self.inner.lock().unwrap();
+        data.refresh(path)?;
+
+        let timestamp_of = |pos: &usize| data.entries[*pos].entry.timestamp;
+        let lower = query.from.map_or(0, |from| {
+            data.order.partition_point(|p| timestamp_of(p) < from)
+        });
+        let mut upper = query.to.map_or(data.order.len(), |to| {
+            data.order.partition_point(|p| timestamp_of(p) <= to)
+        });
+        if let Some(cursor) = cursor {
+            upper = upper.min(
+                data.order
+                    .partition_point(|p| data.entries[*p].key() < cursor),
+            );
+        }
+
+        let mut entries: Vec<usize> = Vec::new();
+        let mut next_cursor = None;
+
+        for &pos in data.order[lower..upper.max(lower)].iter().rev() {
+            let indexed = &data.entries[pos];
+            if !matches_filters(indexed, query) || !matcher.matches(indexed) {
+                continue;
+            }
+            if let (true, Some(&last)) = (entries.len() == limit, entries.last()) {
+                // Another match exists, so the page ends at the last entry collected
+                let (timestamp, line) = data.entries[last].key();
+                next_cursor = Some(format!("{timestamp}:{line}"));
+                break;
+            }
+            entries.push(pos);
+        }
+
+        Ok(HistoryPage {
+            entries: entries
+                .into_iter()
+                .map(|pos| data.entries[pos].entry.clone())
+                .collect(),
+            next_cursor,
+        })
+    }
+}
+
+impl IndexData {
+    fn refresh(&mut self, path: &Path) -> Result<(), String> {
+        let mut file =
+            fs::File::open(path).map_err(|e| format!("Failed to open history.jsonl: {e}"))?;
+        let len = file
+            .metadata()
+            .map_err(|e| format!("Failed to open history.jsonl: {e}"))?
+            .len();
+
+        // A shorter file, or one that starts differently, was rewritten or
+        // truncated; start over
+        if len < self.indexed_len || !self.same_head(&mut file)? {
+            *self = IndexData::default();
+        }
+        if len == self.indexed_len {
+            return Ok(());
+        }
+
+        file.seek(SeekFrom::Start(self.indexed_len))
+            .map_err(|e| format!("Failed to read history.jsonl: {e}"))?;
+        let mut appended = Vec::with_capacity((len - self.indexed_len) as usize);
+        file.take(len - self.indexed_len)
+            .read_to_end(&mut appended)
+            .map_err(|e| format!("Failed to read history.jsonl: {e}"))?;
+
+        // Leave a partially written last line for the next refresh
+        let Some(complete) = appended.iter().rposition(|b| *b == b'\n').map(|i| i + 1) else {
+            return Ok(());
+        };
+        self.indexed_len += complete as u64;
+        let head_missing = HEAD_LEN.saturating_sub(self.head.len()).min(complete);
+        self.head.extend_from_slice(&appended[..head_missing]);
+
+        let first_new = self.entries.len();
+        for line in appended[..complete].split(|b| *b == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+            let line_no = self.next_line;
+            self.next_line += 1;
+
+            let Ok(entry) = serde_json::from_slice::<HistoryEntry>(line) else {
+                continue;
+            };
+            self.entries.push(IndexedEntry {
+                display_lower: entry.display.to_lowercase(),
+                entry,
+                line: line_no,
+            });
+        }
+
+        let first_unsorted = self.order.len().saturating_sub(1);
+        self.order.extend(first_new..self.entries.len());
+
+        // Entries normally arrive in timestamp order; only re-sort when they don't
+        let entries = &self.entries;
+        let in_order = self.order[first_unsorted..]
+            .windows(2)
+            .all(|w| entries[w[0]].key() <= entries[w[1]].key());
+        if !in_order {
+            self.order.sort_by_key(|p| entries[*p].key());
+        }
+
+        Ok(())
+    }
+
+    fn same_head(&self, file: &mut fs::File) -> Result<bool, String> {
+        let mut head = Vec::with_capacity(self.head.len());
+        file.take(self.head.len() as u64)
+            .read_to_end(&mut head)
+            .map_err(|e| format!("Failed to read history.jsonl: {e}"))?;
+        Ok(head == self.head)
+    }
+}
+
+/// Returns the newest `limit` entries by reading `history.jsonl` backwards from
+/// its end. Claude Code appends in timestamp order, so the tail is normally the
+/// answer; if the lines read (plus a small overscan) aren't in order, the whole
+/// file is read and sorted instead.
+pub fn tail(path: &Path, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let len = fs::metadata(path)
+        .map_err(|e| format!("Failed to open history.jsonl: {e}"))?
+        .len();
+
+    let wanted = limit.saturating_add(TAIL_OVERSCAN);
+    let mut entries: Vec<HistoryEntry> = Vec::new();
+    let mut in_order = true;
+
+    for_each_line_backwards(path, len, |_, line| {
+        let Ok(entry) = serde_json::from_slice::<HistoryEntry>(line) else {
+            return true;
+        };
+        if entries
+            .last()
+            .is_some_and(|newer| entry.timestamp > newer.timestamp)
+        {
+            in_order = false;
+            return false;
+        }
+        entries.push(entry);
+        entries.len() < wanted
+    })?;
+
+    if !in_order {
+        return read_all_sorted(path, limit);
+    }
+
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// Reads one page of history for infinite scrolling. The cursor is the byte
+/// offset where the previous page stopped, so pages stay stable while new
+/// prompts are appended to the file.
+pub fn page(path: &Path, cursor: Option<&str>, limit: usize) -> Result<HistoryPage, String> {
+    let limit = limit.clamp(1, MAX_PAGE_SIZE);
+    let len = fs::metadata(path)
+        .map_err(|e| format!("Failed to open history.jsonl: {e}"))?
+        .len();
+    let end = match cursor {
+        Some(cursor) => cursor
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid history cursor '{cursor}'"))?
+            .min(len),
+        None => len,
+    };
+
+    let mut entries: Vec<HistoryEntry> = Vec::new();
+    let mut oldest_offset = end;
+
+    for_each_line_backwards(path, end, |offset, line| {
+        let Ok(entry) = serde_json::from_slice::<HistoryEntry>(line) else {
+            return true;
+        };
+        entries.push(entry);
+        oldest_offset = offset;
+        entries.len() < limit
+    })?;
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+
+    Ok(HistoryPage {
+        next_cursor: (entries.len() == limit && oldest_offset > 0)
+            .then(|| oldest_offset.to_string()),
+        entries,
+    })
+}
+
+// A line that isn't valid UTF-8 is skipped, not the end of the history
+#[allow(clippy::lines_filter_map_ok)]
+fn read_all_sorted(path: &Path, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open history.jsonl: {e}"))?;
+    let reader = BufReader::new(file);
+
+    let mut entries: Vec<HistoryEntry> = reader
+        .lines()
+        .filter_map(|line| line.ok())
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// Calls `visit` with the start offset and bytes of each non-empty line before
+/// `end`, last line first, until it returns `false`.
+fn for_each_line_backwards(
+    path: &Path,
+    end: u64,
+    mut visit: impl FnMut(u64, &[u8]) -> bool,
+) -> Result<(), String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("Failed to open history.jsonl: {e}"))?;
+    let mut pos = end;
+    // Start of a line whose beginning lies in a block not read yet
+    let mut carry: Vec<u8> = Vec::new();
+
+    while pos > 0 {
+        let read = TAIL_BLOCK_SIZE.min(pos);
+        pos -= read;
+
+        let mut block = vec![0; read as usize];
+        file.seek(SeekFrom::Start(pos))
+            .and_then(|_| file.read_exact(&mut block))
+            .map_err(|e| format!("Failed to read history.jsonl: {e}"))?;
+        block.extend_from_slice(&carry);
+
+        let mut line_end = block.len();
+        while let Some(newline) = block[..line_end].iter().rposition(|b| *b == b'\n') {
+            let line = &block[newline + 1..line_end];
+            if !line.is_empty() && !visit(pos + newline as u64 + 1, line) {
+                return Ok(());
+            }
+            line_end = newline;
+        }
+        block.truncate(line_end);
+        carry = block;
+    }
+
+    if !carry.is_empty() {
+        visit(0, &carry);
+    }
+    Ok(())
+}
+
+fn matches_filters(indexed: &IndexedEntry, query: &HistorySearchQuery) -> bool {
+    if let Some(session_id) = &query.session_id {
+        if indexed.entry.session_id.as_ref() != Some(session_id) {
+            return false;
+        }
+    }
+
+    if let Some(project) = &query.project {
+        let Some(entry_project) = indexed.entry.project.as_deref() else {
+            return false;
+        };
+        if entry_project != project && project_name(entry_project) != project {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Last path component of a project directory, accepting either separator.
+pub fn project_name(project: &str) -> &str {
+    project
+        .rsplit(['/', '\\'])
+        .find(|part| !part.is_empty())
+        .unwrap_or(project)
+}
+
+fn parse_cursor(cursor: &str) -> Result<(u64, u64), String> {
+    cursor
+        .split_once(':')
+        .and_then(|(timestamp, line)| Some((timestamp.parse().ok()?, line.parse().ok()?)))
+        .ok_or_else(|| format!("Invalid history cursor '{cursor}'"))
+}
@@ -0,0 +1,66 @@
+use std::sync::{Arc, Mutex};
+
+use tiny_http::{Header, Request, Response, Server, StatusCode};
+
+/// A loopback-only HTTP listener that can be started, moved to another port
+/// or stopped as settings change.
+#[derive(Default)]
+pub struct LocalServer {
+    running: Mutex<Option<Running>>,
+}
+
+struct Running {
+    port: u16,
+    server: Arc<Server>,
+}
+
+impl LocalServer {
+    /// Serves `handler` on `127.0.0.1:port`, or stops serving when `port` is
+    /// `None`. Does nothing if already listening on that port.
+    pub fn sync<F>(&self, name: &str, port: Option<u16>, handler: F) -> Result<(), String>
+    where
+        F: Fn(Request) + Send + 'static,
+    {
+        let mut running = self.running.lock().unwrap();
+        if running.as_ref().map(|r| r.port) == port {
+            return Ok(());
+        }
+        let Some(port) = port else {
+            stop(running.take());
+            return Ok(());
+        };
+
+        // Bound before stopping the previous listener, which keeps serving if
+        // the new port can't be used
+        let server = Server::http(("127.0.0.1", port))
+            .map(Arc::new)
+            .map_err(|e| format!("Failed to start {name} on port {port}: {e}"))?;
+        stop(running.take());
+        let worker = server.clone();
+        std::thread::spawn(move || {
+            for request in worker.incoming_requests() {
+                handler(request);
+            }
+        });
+
+        *running = Some(Running { port, server });
+        Ok(())
+    }
+}
+
+fn stop(running: Option<Running>) {
+    if let Some(previous) = running {
+        // Ends the worker's request loop; the socket closes with the last Arc
+        previous.server.unblock();
+    }
+}
+
+pub fn respond(request: Request, status: u16, content_type: &str, body: String) {
+    let mut response = Response::from_string(body).with_status_code(StatusCode(status));
+    if let Ok(header) = Header::from_bytes("Content-Type", content_type) {
+        response.add_header(header);
+    }
+    if let Err(e) = request.respond(response) {
+        eprintln!("http response error: {e}");
+    }
+}
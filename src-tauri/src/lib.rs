@@ -1,30 +1,92 @@
+mod alerts;
+mod api;
+mod autostart;
+mod budgets;
+mod cache;
 mod commands;
+mod digest;
+mod export;
+mod extra_usage;
+mod five_hour;
+mod history;
+mod http;
+mod metrics;
+mod model_mix;
 mod models;
+mod pricing;
+mod sessions;
+mod settings;
+mod subagents;
+mod timeseries;
+mod tools;
 mod tray;
+mod usage;
 mod watcher;
+mod webhook;
 
+use models::{Settings, UpdateChannel, UpdateProgress};
 use std::fs;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::Emitter;
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
 use tauri_plugin_notification::NotificationExt;
-use tauri_plugin_updater::UpdaterExt;
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+
+/// `brew-status check-budget`: exit code for a Claude Code hook.
+pub fn check_budget() -> i32 {
+    budgets::check_hook()
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .manage(history::HistoryIndex::default())
+        .manage(sessions::SessionIndex::default())
+        .manage(metrics::MetricsServer::default())
+        .manage(api::ApiServer::default())
+        .manage(alerts::AlertState::default())
+        .manage(budgets::BudgetState::default())
+        .manage(extra_usage::ExtraUsageState::default())
+        .manage(digest::DigestState::default())
         .plugin(tauri_plugin_positioner::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .invoke_handler(tauri::generate_handler![
             commands::get_stats_cache,
             commands::get_today_summary,
             commands::get_history,
+            commands::get_history_page,
+            commands::search_history,
+            commands::get_transcript,
+            commands::open_transcript,
             commands::get_daily_token_totals,
+            commands::export_usage,
             commands::resize_popup,
             commands::get_usage_limits,
             commands::get_profile,
             commands::get_app_version,
+            commands::get_settings,
+            commands::update_settings,
+            commands::get_api_token_path,
+            commands::test_webhook,
+            commands::send_digest,
+            commands::choose_digest_directory,
+            commands::get_budget_status,
+            commands::get_budget_hook_command,
+            commands::get_extra_usage_history,
+            commands::get_timeseries,
+            commands::get_weekly_pattern,
+            commands::get_tool_usage,
+            commands::get_mcp_usage,
+            commands::get_subagent_usage,
+            commands::get_cache_report,
+            commands::get_model_mix,
+            commands::get_five_hour_window,
             commands::check_for_updates,
         ])
         .setup(|app| {
@@ -35,13 +97,25 @@ pub fn run() {
                 let _ = app.set_activation_policy(tauri::ActivationPolicy::Accessory);
             }
 
+            let settings = settings::load();
+            if let Err(e) = autostart::sync(settings.launch_at_login) {
+                eprintln!("launch at login error: {e}");
+            }
+            if let Err(e) = metrics::sync(&handle, &settings) {
+                eprintln!("metrics exporter error: {e}");
+            }
+            if let Err(e) = api::sync(&handle, &settings) {
+                eprintln!("local API error: {e}");
+            }
+
             tray::setup_tray(&handle)?;
+            tray::start_usage_poller(handle.clone());
             watcher::start_watcher(handle.clone());
+            alerts::start_daily_summary(handle.clone());
+            digest::start_digest_scheduler(handle.clone());
+            budgets::refresh_now(&handle);
 
-            let update_handle = handle.clone();
-            tauri::async_runtime::spawn(async move {
-                check_for_updates(update_handle).await;
-            });
+            start_update_scheduler(handle.clone());
 
             Ok(())
         })
@@ -60,29 +134,32 @@ fn check_file_path(identifier: &str) -> Option<std::path::PathBuf> {
     dirs::data_dir().map(|d| d.join(identifier).join("last_update_check"))
 }
 
-fn should_check() -> bool {
-    let Some(primary_path) = check_file_path("com.brewstatus") else {
-        return true;
-    };
+fn should_check(settings: &Settings) -> bool {
+    let now = now_secs();
+    if !settings.auto_check_updates
+        || settings
+            .update_checks_paused_until
+            .is_some_and(|until| now < until)
+    {
+        return false;
+    }
 
-    match fs::read_to_string(&primary_path) {
-        Ok(contents) => {
-            let last_primary: u64 = contents.trim().parse().unwrap_or(0);
-            now_secs().saturating_sub(last_primary) >= 86400
-        }
-        Err(_) => match check_file_path("com.brewstatus.app") {
-            Some(legacy_path) => match fs::read_to_string(legacy_path) {
-                Ok(contents) => {
-                    let last_legacy: u64 = contents.trim().parse().unwrap_or(0);
-                    now_secs().saturating_sub(last_legacy) >= 86400
-                }
-                Err(_) => true,
-            },
-            None => true,
-        }
+    let interval_secs = u64::from(settings.update_check_interval_hours.max(1)) * 3600;
+    match last_check_secs() {
+        Some(last) => now.saturating_sub(last) >= interval_secs,
+        None => true,
     }
 }
 
+fn last_check_secs() -> Option<u64> {
+    let read = |identifier: &str| {
+        let contents = fs::read_to_string(check_file_path(identifier)?).ok()?;
+        Some(contents.trim().parse().unwrap_or(0))
+    };
+
+    read("com.brewstatus").or_else(|| read("com.brewstatus.app"))
+}
+
 fn write_check_timestamp() {
     if let Some(path) = check_file_path("com.brewstatus") {
         if let Some(parent) = path.parent() {
@@ -92,43 +169,163 @@ fn write_check_timestamp() {
     }
 }
 
-pub async fn prompt_install(update: tauri_plugin_updater::Update, handle: tauri::AppHandle) {
+const STABLE_ENDPOINT: &str =
+    "https://github.com/hicap-oss/brew-status/releases/latest/download/latest.json";
+// Rolling release whose manifest always points at the newest build, prereleases included
+const BETA_ENDPOINT: &str =
+    "https://github.com/hicap-oss/brew-status/releases/download/beta/latest.json";
+const MAX_NOTES_IN_PROMPT: usize = 800;
+const UPDATE_SCHEDULER_TICK: Duration = Duration::from_secs(30 * 60);
+
+const INSTALL_LABEL: &str = "Install";
+const SKIP_LABEL: &str = "Skip This Version";
+const LATER_LABEL: &str = "Later";
+
+/// Builds an updater pointed at the endpoint for the channel chosen in settings.
+pub fn channel_updater(handle: &tauri::AppHandle) -> Result<Updater, String> {
+    let endpoint = match settings::load().update_channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    };
+    let url = endpoint
+        .parse()
+        .map_err(|e| format!("Invalid update endpoint: {e}"))?;
+
+    handle
+        .updater_builder()
+        .endpoints(vec![url])
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Updater init error: {e}"))
+}
+
+fn prompt_message(update: &Update) -> String {
+    let mut message = format!(
+        "Brew Status v{} is available (you have v{}).",
+        update.version, update.current_version
+    );
+
+    if let Some(notes) = update
+        .body
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    {
+        message.push_str("\n\nWhat's new:\n");
+        if notes.chars().count() > MAX_NOTES_IN_PROMPT {
+            message.extend(notes.chars().take(MAX_NOTES_IN_PROMPT));
+            message.push('\u{2026}');
+        } else {
+            message.push_str(notes);
+        }
+    }
+
+    message.push_str("\n\nWould you like to install the update now?");
+    message
+}
+
+pub async fn prompt_install(update: Update, handle: tauri::AppHandle) {
     let version = update.version.clone();
 
-    let (sender, receiver) = std::sync::mpsc::channel();
+    // Awaited rather than blocked on, so the async runtime thread stays free
+    // while the dialog is open
+    let (sender, receiver) = tokio::sync::oneshot::channel();
     handle
         .dialog()
-        .message(format!(
-            "Brew Status v{version} is available.\n\nWould you like to install the update now?"
-        ))
+        .message(prompt_message(&update))
         .title("Update Available")
         .kind(MessageDialogKind::Info)
-        .buttons(MessageDialogButtons::OkCancelCustom(
-            "Install".into(),
-            "Later".into(),
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            INSTALL_LABEL.into(),
+            SKIP_LABEL.into(),
+            LATER_LABEL.into(),
         ))
-        .show(move |confirmed| {
-            let _ = sender.send(confirmed);
+        .show_with_result(move |result| {
+            let _ = sender.send(result);
         });
 
-    let confirmed = receiver.recv().unwrap_or(false);
+    let result = receiver.await.unwrap_or_default();
+    let choice = match &result {
+        MessageDialogResult::Yes | MessageDialogResult::Ok => INSTALL_LABEL,
+        MessageDialogResult::No => SKIP_LABEL,
+        MessageDialogResult::Custom(label) => label.as_str(),
+        MessageDialogResult::Cancel => LATER_LABEL,
+    };
+
+    match choice {
+        INSTALL_LABEL => install_update(update, handle).await,
+        SKIP_LABEL => {
+            let mut settings = settings::load();
+            settings.skipped_version = Some(version);
+            if let Err(e) = settings::save(&settings) {
+                eprintln!("{e}");
+            }
+            let _ = handle.emit("settings-updated", ());
+        }
+        _ => {}
+    }
+}
+
+async fn install_update(update: Update, handle: tauri::AppHandle) {
+    let version = update.version.clone();
+    let mut downloaded: u64 = 0;
+    let progress_handle = handle.clone();
+    let progress_version = version.clone();
+
+    let result = update
+        .download_and_install(
+            |chunk_length, content_length| {
+                downloaded += chunk_length as u64;
+                let _ = progress_handle.emit(
+                    "update-progress",
+                    UpdateProgress {
+                        version: progress_version.clone(),
+                        downloaded,
+                        total: content_length,
+                        finished: false,
+                    },
+                );
+            },
+            || {},
+        )
+        .await;
 
-    if confirmed {
-        if let Err(e) = update.download_and_install(|_, _| {}, || {}).await {
-            eprintln!("update install error: {e}");
+    match result {
+        Ok(()) => {
+            let _ = handle.emit(
+                "update-progress",
+                UpdateProgress {
+                    version,
+                    downloaded,
+                    total: Some(downloaded),
+                    finished: true,
+                },
+            );
         }
+        Err(e) => eprintln!("update install error: {e}"),
     }
 }
 
+/// Re-checks for updates while the app keeps running. The loop wakes up
+/// regularly and `should_check` decides from the last-check file and settings
+/// whether a check is due, so interval changes and pauses apply without a restart.
+fn start_update_scheduler(handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check_for_updates(handle.clone()).await;
+            tokio::time::sleep(UPDATE_SCHEDULER_TICK).await;
+        }
+    });
+}
+
 async fn check_for_updates(handle: tauri::AppHandle) {
-    if !should_check() {
+    if !should_check(&settings::load()) {
         return;
     }
 
-    let updater = match handle.updater() {
+    let updater = match channel_updater(&handle) {
         Ok(u) => u,
         Err(e) => {
-            eprintln!("updater init error: {e}");
+            eprintln!("{e}");
             return;
         }
     };
@@ -145,17 +342,22 @@ async fn check_for_updates(handle: tauri::AppHandle) {
         }
     };
 
+    write_check_timestamp();
+
     let version = update.version.clone();
+    if settings::load().skipped_version.as_deref() == Some(version.as_str()) {
+        return;
+    }
 
     // Send a Windows notification
-    let _ = handle
-        .notification()
-        .builder()
-        .title("Brew Status Update")
-        .body(format!("Brew Status v{version} is available"))
-        .show();
-
-    write_check_timestamp();
+    if settings::load().notifications_enabled {
+        let _ = handle
+            .notification()
+            .builder()
+            .title("Brew Status Update")
+            .body(format!("Brew Status v{version} is available"))
+            .show();
+    }
 
     prompt_install(update, handle).await;
 }
@@ -1,5 +1,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Run as a Claude Code hook instead of starting the app
+    if std::env::args().nth(1).as_deref() == Some("check-budget") {
+        std::process::exit(brew_status_lib::check_budget());
+    }
     brew_status_lib::run()
 }
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::DateTime;
+use tauri::{AppHandle, Manager};
+use tiny_http::Request;
+
+use crate::http::{self, LocalServer};
+use crate::models::{LimitEntry, Settings};
+
+/// Totals re-read every transcript, so scrapes within this window share a result
+const SNAPSHOT_TTL: Duration = Duration::from_secs(60);
+const TOKEN_KINDS: [&str; 4] = ["input", "output", "cache_read", "cache_creation"];
+
+/// Optional Prometheus exporter on `127.0.0.1:<metrics_port>/metrics`.
+#[derive(Default)]
+pub struct MetricsServer {
+    server: LocalServer,
+    snapshot: Mutex<Option<(Instant, Arc<Snapshot>)>>,
+}
+
+#[derive(Default)]
+struct Snapshot {
+    /// Keyed by (model, kind, project)
+    tokens: BTreeMap<(String, &'static str, String), u64>,
+    sessions: u64,
+    messages: u64,
+}
+
+/// Starts, moves or stops the exporter to match the settings.
+pub fn sync(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let state = app.state::<MetricsServer>();
+    let port = settings.metrics_enabled.then_some(settings.metrics_port);
+    let handle = app.clone();
+
+    state.server.sync("metrics exporter", port, move |request| {
+        handle_request(&handle, request)
+    })
+}
+
+fn handle_request(app: &AppHandle, request: Request) {
+    let path = request.url().split('?').next().unwrap_or_default();
+    if path != "/metrics" {
+        http::respond(request, 404, "text/plain", "Not found\n".to_string());
+        return;
+    }
+
+    // Prometheus asks for OpenMetrics first when it supports it
+    let openmetrics = request.headers().iter().any(|h| {
+        h.field.equiv("Accept") && h.value.as_str().contains("application/openmetrics-text")
+    });
+
+    let state = app.state::<MetricsServer>();
+    let current = {
+        let mut cached = state.snapshot.lock().unwrap();
+        match cached.as_ref() {
+            Some((taken_at, current)) if taken_at.elapsed() < SNAPSHOT_TTL => current.clone(),
+            _ => {
+                let current = Arc::new(take_snapshot());
+                *cached = Some((Instant::now(), current.clone()));
+                current
+            }
+        }
+    };
+
+    let body = render(&current, app, openmetrics);
+    let content_type = if openmetrics {
+        "application/openmetrics-text; version=1.0.0; charset=utf-8"
+    } else {
+        "text/plain; version=0.0.4; charset=utf-8"
+    };
+    http::respond(request, 200, content_type, body);
+}
+
+/// Session and message counts come from the stats cache, as everywhere else
+/// in the app; token counters from a scan of the transcripts, which is what
+/// the stats cache is built from when Claude Code hasn't written one.
+fn take_snapshot() -> Snapshot {
+    let mut snapshot = match crate::commands::get_stats_cache() {
+        Ok(stats) => Snapshot {
+            sessions: stats.total_sessions,
+            messages: stats.total_messages,
+            ..Default::default()
+        },
+        Err(e) => {
+            eprintln!("metrics stats error: {e}");
+            Snapshot::default()
+        }
+    };
+
+    let scan = crate::usage::scan(None);
+
+    // The stats cache's model usage, split further by project
+    for record in scan.records.iter().filter(|r| !r.model.is_empty()) {
+        let project = crate::history::project_name(&record.project).to_string();
+        let counts = [
+            record.input_tokens,
+            record.output_tokens,
+            record.cache_read_input_tokens,
+            record.cache_creation_input_tokens,
+        ];
+        for (kind, count) in TOKEN_KINDS.into_iter().zip(counts) {
+            *snapshot
+                .tokens
+                .entry((record.model.clone(), kind, project.clone()))
+                .or_insert(0) += count;
+        }
+    }
+
+    snapshot
+}
+
+fn render(snapshot: &Snapshot, app: &AppHandle, openmetrics: bool) -> String {
+    let mut out = String::new();
+
+    family(
+        &mut out,
+        "brew_status_tokens",
+        "counter",
+        "Claude Code tokens by model, token kind and project.",
+        openmetrics,
+    );
+    for ((model, kind, project), count) in &snapshot.tokens {
+        let _ = writeln!(
+            out,
+            "brew_status_tokens_total{{model=\"{}\",kind=\"{kind}\",project=\"{}\"}} {count}",
+            escape_label(model),
+            escape_label(project),
+        );
+    }
+
+    family(
+        &mut out,
+        "brew_status_sessions",
+        "counter",
+        "Claude Code sessions.",
+        openmetrics,
+    );
+    let _ = writeln!(out, "brew_status_sessions_total {}", snapshot.sessions);
+
+    family(
+        &mut out,
+        "brew_status_messages",
+        "counter",
+        "Claude Code user and assistant messages.",
+        openmetrics,
+    );
+    let _ = writeln!(out, "brew_status_messages_total {}", snapshot.messages);
+
+    if let Some(limits) = crate::tray::latest_limits(app) {
+        let windows: Vec<(&str, &LimitEntry)> = crate::tray::limit_windows(&limits)
+            .map(|(window, _, entry)| (window, entry))
+            .collect();
+
+        family(
+            &mut out,
+            "brew_status_limit_utilization",
+            "gauge",
+            "Fraction of the rate limit window used (0-1).",
+            openmetrics,
+        );
+        for (window, entry) in &windows {
+            let _ = writeln!(
+                out,
+                "brew_status_limit_utilization{{window=\"{window}\"}} {}",
+                entry.utilization / 100.0
+            );
+        }
+
+        family(
+            &mut out,
+            "brew_status_limit_resets_at_seconds",
+            "gauge",
+            "Unix time at which the rate limit window resets.",
+            openmetrics,
+        );
+        for (window, entry) in &windows {
+            let Some(resets_at) = entry
+                .resets_at
+                .as_deref()
+                .and_then(|r| DateTime::parse_from_rfc3339(r).ok())
+            else {
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "brew_status_limit_resets_at_seconds{{window=\"{window}\"}} {}",
+                resets_at.timestamp()
+            );
+        }
+
+        if let Some(extra) = limits.extra_usage.as_ref().filter(|extra| extra.is_enabled) {
+            if let Some(used) = extra.used_credits {
+                family(
+                    &mut out,
+                    "brew_status_extra_usage_used_credits",
+                    "gauge",
+                    "Extra usage credits spent this billing cycle.",
+                    openmetrics,
+                );
+                let _ = writeln!(out, "brew_status_extra_usage_used_credits {used}");
+            }
+            if let Some(limit) = extra.monthly_limit {
+                family(
+                    &mut out,
+                    "brew_status_extra_usage_limit_credits",
+                    "gauge",
+                    "Monthly extra usage credit limit.",
+                    openmetrics,
+                );
+                let _ = writeln!(out, "brew_status_extra_usage_limit_credits {limit}");
+            }
+        }
+    }
+
+    if openmetrics {
+        out.push_str("# EOF\n");
+    }
+    out
+}
+
+/// Writes HELP/TYPE lines. The Prometheus text format names counters with
+/// their `_total` suffix; OpenMetrics uses the bare family name.
+fn family(out: &mut String, name: &str, kind: &str, help: &str, openmetrics: bool) {
+    let name = if kind == "counter" && !openmetrics {
+        format!("{name}_total")
+    } else {
+        name.to_string()
+    };
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
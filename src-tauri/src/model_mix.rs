@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::models::{
+    ModelLimitUsage, ModelMix, ModelMixDay, ModelSwitch, TimeRange, UsageLimits, UsageShare,
+};
+use crate::usage::{by_tokens, percent, UsageRecord};
+
+const WEEKLY_WINDOW: TimeDelta = TimeDelta::days(7);
+
+/// A model id split into family, version and snapshot date, e.g.
+/// `claude-sonnet-4-5-20250929` is Sonnet 4.5 from 2025-09-29.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelInfo {
+    /// `opus`, `sonnet`, `haiku` or `other`
+    pub family: &'static str,
+    pub version: Option<String>,
+    pub snapshot: Option<String>,
+}
+
+impl ModelInfo {
+    /// e.g. `Sonnet 4.5`
+    pub fn name(&self) -> String {
+        let family = match self.family {
+            "opus" => "Opus",
+            "sonnet" => "Sonnet",
+            "haiku" => "Haiku",
+            _ => "Other",
+        };
+        match &self.version {
+            Some(version) => format!("{family} {version}"),
+            None => family.to_string(),
+        }
+    }
+}
+
+/// Understands both id styles, `claude-sonnet-4-5-20250929` and
+/// `claude-3-5-haiku-20241022`, plus suffixes like `[1m]`.
+pub fn normalize(model: &str) -> ModelInfo {
+    let model = model.to_ascii_lowercase();
+    let model = model.split('[').next().unwrap_or_default();
+    let mut family = "other";
+    let mut version = Vec::new();
+    let mut snapshot = None;
+    for part in model.trim_start_matches("claude-").split(['-', '@']) {
+        match part {
+            "opus" => family = "opus",
+            "sonnet" => family = "sonnet",
+            "haiku" => family = "haiku",
+            _ if part.len() == 8 && part.bytes().all(|b| b.is_ascii_digit()) => {
+                snapshot = Some(format!("{}-{}-{}", &part[..4], &part[4..6], &part[6..]));
+            }
+            _ if !part.is_empty()
+                && part.len() <= 2
+                && part.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                version.push(part)
+            }
+            _ => {}
+        }
+    }
+
+    ModelInfo {
+        family,
+        version: (!version.is_empty()).then(|| version.join(".")),
+        snapshot,
+    }
+}
+
+/// Usage per model family and version over the range, the daily mix,
+/// in-session switches, and how Sonnet and Opus usage lines up with their
+/// weekly limits.
+pub fn report(range: &TimeRange, limits: Option<&UsageLimits>) -> Result<ModelMix, String> {
+    let (from, to) = crate::usage::report_range(range)?;
+    let limit_windows: Vec<(&str, &str, f64, DateTime<Local>)> = limits
+        .map(|limits| {
+            [
+                ("seven_day_sonnet", "sonnet", &limits.seven_day_sonnet),
+                ("seven_day_opus", "opus", &limits.seven_day_opus),
+            ]
+            .into_iter()
+            .filter_map(|(window, family, entry)| {
+                let entry = entry.as_ref()?;
+                let resets_at = DateTime::parse_from_rfc3339(entry.resets_at.as_deref()?).ok()?;
+                Some((
+                    window,
+                    family,
+                    entry.utilization,
+                    resets_at.with_timezone(&Local),
+                ))
+            })
+            .collect()
+        })
+        .unwrap_or_default();
+    let since = limit_windows
+        .iter()
+        .map(|(_, _, _, resets_at)| *resets_at - WEEKLY_WINDOW)
+        .fold(from, DateTime::min);
+    // One scan covers both the range and the limit windows
+    let scan = crate::usage::scan(Some(since));
+    // `<synthetic>` and other non-Claude ids carry no real usage
+    let records: Vec<(&UsageRecord, ModelInfo)> = scan
+        .records
+        .iter()
+        .filter(|r| crate::pricing::price_for(&r.model).is_some())
+        .map(|r| (r, normalize(&r.model)))
+        .collect();
+    let in_range: Vec<&(&UsageRecord, ModelInfo)> = records
+        .iter()
+        .filter(|(r, _)| r.timestamp >= from && r.timestamp < to)
+        .collect();
+
+    let mut families: HashMap<String, UsageShare> = HashMap::new();
+    let mut models: HashMap<String, UsageShare> = HashMap::new();
+    let mut daily: BTreeMap<String, HashMap<String, u64>> = BTreeMap::new();
+    let mut current: HashMap<&str, String> = HashMap::new();
+    let mut switches = Vec::new();
+    for (record, info) in &in_range {
+        let name = info.name();
+        for (rows, key) in [(&mut families, info.family), (&mut models, name.as_str())] {
+            rows.entry(key.to_string())
+                .or_insert_with(|| UsageShare::new(key))
+                .add(record);
+        }
+        *daily
+            .entry(record.timestamp.format("%Y-%m-%d").to_string())
+            .or_default()
+            .entry(name.clone())
+            .or_insert(0) += record.total_tokens();
+
+        // Subagents often run on another model by design
+        if record.sidechain {
+            continue;
+        }
+        if let Some(previous) = current.insert(&record.session_id, name.clone()) {
+            if previous != name {
+                switches.push(ModelSwitch {
+                    session_id: record.session_id.clone(),
+                    project: record.project.clone(),
+                    timestamp: record.timestamp.to_rfc3339(),
+                    from: previous,
+                    to: name,
+                });
+            }
+        }
+    }
+
+    let limits = limit_windows
+        .into_iter()
+        .map(|(window, family, utilization, resets_at)| {
+            let start = resets_at - WEEKLY_WINDOW;
+            let total_tokens = records
+                .iter()
+                .filter(|(r, info)| info.family == family && r.timestamp >= start)
+                .map(|(r, _)| r.total_tokens())
+                .sum::<u64>();
+            ModelLimitUsage {
+                window: window.to_string(),
+                family: family.to_string(),
+                start: start.to_rfc3339(),
+                resets_at: resets_at.to_rfc3339(),
+                utilization,
+                total_tokens,
+                tokens_per_percent: crate::five_hour::tokens_per_percent(total_tokens, utilization),
+            }
+        })
+        .collect();
+
+    let total_tokens: u64 = in_range.iter().map(|(r, _)| r.total_tokens()).sum();
+    Ok(ModelMix {
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        families: by_tokens(families, total_tokens),
+        models: by_tokens(models, total_tokens),
+        daily: daily
+            .into_iter()
+            .map(|(date, tokens)| {
+                let day_total: u64 = tokens.values().sum();
+                ModelMixDay {
+                    date,
+                    total_tokens: day_total,
+                    shares: tokens
+                        .into_iter()
+                        .map(|(name, tokens)| (name, percent(tokens, day_total)))
+                        .collect(),
+                }
+            })
+            .collect(),
+        switches,
+        limits,
+    })
+}
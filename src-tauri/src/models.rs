@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // OAuth credentials from ~/.claude/.credentials.json
 #[derive(Debug, Clone, Deserialize)]
@@ -140,6 +140,164 @@ pub struct HistoryEntry {
     pub project: Option<String>,
     #[serde(default)]
     pub session_id: Option<String>,
+    /// Usage of the linked session transcript, filled in by history pages
+    #[serde(default)]
+    pub session: Option<SessionSummary>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    /// Estimated at API list prices
+    pub cost_usd: f64,
+    /// Models used, most tokens first
+    pub models: Vec<String>,
+    /// Main conversation messages; subagent traffic is counted below
+    pub message_count: u64,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub duration_ms: u64,
+    /// Prompts, tool results and replies exchanged with subagents
+    pub subagent_message_count: u64,
+    /// Tokens and cost of subagent (sidechain) replies, included in the
+    /// totals above
+    pub subagent_tokens: u64,
+    pub subagent_cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptPage {
+    pub session_id: String,
+    pub messages: Vec<TranscriptMessage>,
+    /// Number of messages in the whole session
+    pub total: usize,
+    pub next_offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMessage {
+    pub uuid: Option<String>,
+    /// "user" or "assistant"
+    pub role: String,
+    pub timestamp: Option<String>,
+    pub model: Option<String>,
+    pub is_sidechain: bool,
+    pub blocks: Vec<TranscriptBlock>,
+    pub usage: Option<TurnUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum TranscriptBlock {
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    ToolUse {
+        id: Option<String>,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: Option<String>,
+        content: String,
+        is_error: bool,
+        truncated: bool,
+    },
+    Image {
+        media_type: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistorySearchQuery {
+    pub text: Option<String>,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub project: Option<String>,
+    pub session_id: Option<String>,
+    /// Inclusive lower bound, Unix milliseconds
+    pub from: Option<u64>,
+    /// Inclusive upper bound, Unix milliseconds
+    pub to: Option<u64>,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRequest {
+    pub format: ExportFormat,
+    /// Inclusive, `YYYY-MM-DD` in local time
+    pub from: Option<String>,
+    /// Inclusive, `YYYY-MM-DD` in local time
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageRow {
+    pub key: String,
+    /// Project for session rows; empty otherwise
+    pub label: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub messages: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageExport {
+    pub generated_at: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub daily: Vec<UsageRow>,
+    pub models: Vec<UsageRow>,
+    pub projects: Vec<UsageRow>,
+    pub sessions: Vec<UsageRow>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -147,6 +305,17 @@ pub struct HistoryEntry {
 pub struct UpdateResult {
     pub update_available: bool,
     pub version: Option<String>,
+    pub notes: Option<String>,
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProgress {
+    pub version: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub finished: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -159,3 +328,613 @@ pub struct TodaySummary {
     pub sessions: u64,
     pub tool_calls: u64,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub notifications_enabled: bool,
+    pub launch_at_login: bool,
+    pub update_channel: UpdateChannel,
+    pub skipped_version: Option<String>,
+    pub auto_check_updates: bool,
+    pub update_check_interval_hours: u32,
+    /// Unix timestamp (seconds) until which automatic checks are paused
+    pub update_checks_paused_until: Option<u64>,
+    /// Serve Prometheus metrics on 127.0.0.1
+    pub metrics_enabled: bool,
+    pub metrics_port: u16,
+    /// Serve the JSON API on 127.0.0.1, authenticated with the api-token file
+    pub api_enabled: bool,
+    pub api_port: u16,
+    pub webhooks: Vec<WebhookConfig>,
+    /// Utilization percentages that trigger a limit alert when crossed
+    pub alert_thresholds: Vec<u32>,
+    /// Local hour at which to send the daily summary; `None` disables it
+    pub daily_summary_hour: Option<u32>,
+    /// Date (`YYYY-MM-DD`) of the last daily summary sent
+    pub last_daily_summary: Option<String>,
+    /// Send a digest once each day / week is over
+    pub digest_daily: bool,
+    pub digest_weekly: bool,
+    /// Directory digest reports are written to; `None` skips the file
+    pub digest_directory: Option<String>,
+    pub digest_format: DigestFormat,
+    /// Also post digests to the alert webhooks
+    pub digest_webhooks: bool,
+    /// First day (`YYYY-MM-DD`) of the last daily / weekly digest sent
+    pub last_daily_digest: Option<String>,
+    pub last_weekly_digest: Option<String>,
+    pub budgets: Vec<Budget>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            notifications_enabled: true,
+            launch_at_login: false,
+            update_channel: UpdateChannel::Stable,
+            skipped_version: None,
+            auto_check_updates: true,
+            update_check_interval_hours: 24,
+            update_checks_paused_until: None,
+            metrics_enabled: false,
+            metrics_port: 9464,
+            api_enabled: false,
+            api_port: 9465,
+            webhooks: Vec::new(),
+            alert_thresholds: vec![75, 90],
+            daily_summary_hour: None,
+            last_daily_summary: None,
+            digest_daily: false,
+            digest_weekly: false,
+            digest_directory: None,
+            digest_format: DigestFormat::Markdown,
+            digest_webhooks: false,
+            last_daily_digest: None,
+            last_weekly_digest: None,
+            budgets: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub format: WebhookFormat,
+    /// JSON body with `{{field}}` placeholders; empty uses the format's default
+    pub template: String,
+    pub enabled: bool,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            format: WebhookFormat::Json,
+            template: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    #[default]
+    Json,
+    Slack,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub title: String,
+    pub message: String,
+    /// Limit window, e.g. `five_hour`
+    pub window: Option<String>,
+    pub utilization: Option<f64>,
+    pub resets_at: Option<String>,
+    pub summary: Option<TodaySummary>,
+    pub digest: Option<Digest>,
+    pub budget: Option<BudgetStatus>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    Threshold,
+    Reset,
+    DailySummary,
+    Digest,
+    Budget,
+    ExtraUsage,
+    Test,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Budget {
+    pub name: String,
+    pub period: BudgetPeriod,
+    pub unit: BudgetUnit,
+    pub limit: f64,
+    /// Only count usage in this project (directory name or full path)
+    pub project: Option<String>,
+    /// Only count models whose id contains this, e.g. `opus`
+    pub model: Option<String>,
+    /// Percentage of the limit at which the budget turns to warning
+    pub warn_percent: u32,
+    /// Make `brew-status check-budget` fail once the budget is exceeded
+    pub block: bool,
+    pub enabled: bool,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            period: BudgetPeriod::Daily,
+            unit: BudgetUnit::Usd,
+            limit: 0.0,
+            project: None,
+            model: None,
+            warn_percent: 80,
+            block: false,
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    #[default]
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetUnit {
+    #[default]
+    Usd,
+    Tokens,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetLevel {
+    Ok,
+    Warning,
+    Exceeded,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    pub budget: Budget,
+    /// Estimated USD or tokens, matching the budget's unit
+    pub used: f64,
+    pub percent: f64,
+    pub level: BudgetLevel,
+    pub period_start: String,
+    pub resets_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraUsageSample {
+    pub timestamp: String,
+    pub used_credits: f64,
+    pub monthly_limit: Option<f64>,
+    pub utilization: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraUsageDay {
+    pub date: String,
+    /// Credits used so far this cycle, as of the end of the day
+    pub used_credits: f64,
+    /// Credits consumed during the day
+    pub spent: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraUsageProjection {
+    pub cycle_start: String,
+    pub cycle_end: String,
+    pub used_credits: f64,
+    /// Credits used by the end of the cycle at the current pace
+    pub projected_credits: f64,
+    pub monthly_limit: Option<f64>,
+    pub projected_utilization: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraUsageHistory {
+    pub samples: Vec<ExtraUsageSample>,
+    pub daily: Vec<ExtraUsageDay>,
+    pub projection: Option<ExtraUsageProjection>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestPeriod {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Digest {
+    pub period: DigestPeriod,
+    /// First and last day covered, inclusive
+    pub start: String,
+    pub end: String,
+    pub totals: DigestTotals,
+    /// Same-length period immediately before this one
+    pub previous: DigestTotals,
+    pub models: Vec<DigestRow>,
+    pub top_projects: Vec<DigestRow>,
+    pub longest_session: Option<DigestSession>,
+    /// Local hour (0-23) with the most assistant replies
+    pub busiest_hour: Option<u32>,
+    /// Highest utilization seen per limit window, keyed like `five_hour`
+    pub limit_peaks: BTreeMap<String, f64>,
+    pub generated_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestTotals {
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub messages: u64,
+    pub sessions: u64,
+    pub tool_calls: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestRow {
+    pub name: String,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestSession {
+    pub session_id: String,
+    pub project: String,
+    pub duration_ms: u64,
+    pub messages: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyPattern {
+    pub metric: TimeseriesMetric,
+    pub from: String,
+    pub to: String,
+    /// `cells[weekday][hour]`, Monday first, in local time
+    pub cells: Vec<Vec<f64>>,
+    pub max: f64,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolUsage {
+    pub from: String,
+    pub to: String,
+    pub calls: u64,
+    pub errors: u64,
+    /// Percent of calls whose result was an error
+    pub error_rate: f64,
+    /// Most used first; MCP tools are grouped per server
+    pub tools: Vec<ToolStat>,
+    pub daily: Vec<ToolBreakdown>,
+    /// Most calls first
+    pub projects: Vec<ToolBreakdown>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStat {
+    /// Tool name, or `mcp__<server>` for an MCP server's tools
+    pub name: String,
+    pub mcp_server: Option<String>,
+    pub calls: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+}
+
+/// Tool calls for one day or project, keyed by [`ToolStat::name`].
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolBreakdown {
+    /// `YYYY-MM-DD` or the project path
+    pub key: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub tools: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpUsage {
+    pub from: String,
+    pub to: String,
+    /// Most calls first
+    pub servers: Vec<McpServerStat>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerStat {
+    pub server: String,
+    pub calls: u64,
+    pub errors: u64,
+    /// Size of the results returned to the model
+    pub result_bytes: u64,
+    /// Assistant turns calling the server, and their tokens and estimated
+    /// cost. A turn calling several servers counts towards each of them.
+    pub turns: u64,
+    pub turn_tokens: u64,
+    pub turn_cost_usd: f64,
+    /// Most calls first
+    pub tools: Vec<McpToolStat>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolStat {
+    pub name: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub result_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubagentUsage {
+    pub from: String,
+    pub to: String,
+    pub main: UsageTotals,
+    pub subagents: UsageTotals,
+    /// Percent of all tokens used by subagents
+    pub subagent_share: f64,
+    /// Sessions that ran subagents, most subagent tokens first
+    pub sessions: Vec<SubagentSession>,
+    pub daily: Vec<SubagentDay>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTotals {
+    pub replies: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubagentSession {
+    /// The parent session
+    pub session_id: String,
+    pub project: String,
+    /// Distinct subagents seen, when transcripts record their ids
+    pub agents: u64,
+    pub main: UsageTotals,
+    pub subagents: UsageTotals,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubagentDay {
+    pub date: String,
+    pub main_tokens: u64,
+    pub subagent_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheReport {
+    pub from: String,
+    pub to: String,
+    pub totals: CacheStats,
+    pub daily: Vec<CacheStats>,
+    /// Most prompt tokens first
+    pub projects: Vec<CacheStats>,
+    /// Most prompt tokens first
+    pub sessions: Vec<CacheStats>,
+    /// Sessions that kept rebuilding their cache, most rebuilt tokens first
+    pub rebuilding_sessions: Vec<CacheStats>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    /// Date, project path or session id
+    pub key: String,
+    /// Project for session rows; empty otherwise
+    pub label: String,
+    pub input_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cache_write_5m_tokens: u64,
+    pub cache_write_1h_tokens: u64,
+    /// Percent of prompt tokens (input, cache reads and writes) read from
+    /// the cache
+    pub hit_ratio: f64,
+    /// Estimated USD saved by cache reads compared with sending the same
+    /// tokens uncached, less the extra paid for cache writes
+    pub savings_usd: f64,
+    /// Turns after the first that wrote more to the cache than they read
+    pub rebuilds: u64,
+    pub rebuild_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelMix {
+    pub from: String,
+    pub to: String,
+    /// Per family (`sonnet`), most tokens first
+    pub families: Vec<UsageShare>,
+    /// Per family and version (`Sonnet 4.5`), most tokens first
+    pub models: Vec<UsageShare>,
+    pub daily: Vec<ModelMixDay>,
+    /// Main-conversation model changes within a session, oldest first
+    pub switches: Vec<ModelSwitch>,
+    /// Family usage in the current window of each model-specific limit
+    pub limits: Vec<ModelLimitUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelMixDay {
+    pub date: String,
+    pub total_tokens: u64,
+    /// Percent of the day's tokens per family and version
+    pub shares: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSwitch {
+    pub session_id: String,
+    pub project: String,
+    pub timestamp: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelLimitUsage {
+    /// `seven_day_sonnet` or `seven_day_opus`
+    pub window: String,
+    pub family: String,
+    /// Seven days before `resets_at`
+    pub start: String,
+    pub resets_at: String,
+    pub utilization: f64,
+    /// The family's local tokens since `start`
+    pub total_tokens: u64,
+    pub tokens_per_percent: Option<f64>,
+}
+
+/// The current five-hour limit window rebuilt from local transcripts.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FiveHourWindow {
+    /// Inferred as five hours before `resets_at`
+    pub start: String,
+    pub resets_at: Option<String>,
+    /// Server-side utilization, percent
+    pub utilization: f64,
+    /// Messages as the stats cache counts them: prompts, tool results and
+    /// assistant replies
+    pub messages: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub models: Vec<UsageShare>,
+    pub projects: Vec<UsageShare>,
+    /// Local tokens per percent of utilization; `None` while too little of
+    /// the window is used to tell
+    pub tokens_per_percent: Option<f64>,
+    /// Tokens left at that rate before the window is used up
+    pub remaining_tokens: Option<u64>,
+}
+
+/// Usage of one model or project within a report.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageShare {
+    pub name: String,
+    /// Model ids seen
+    pub ids: Vec<String>,
+    /// Snapshot dates (YYYY-MM-DD) among those ids
+    pub snapshots: Vec<String>,
+    pub replies: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    /// Percent of the report's tokens
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeseriesMetric {
+    Tokens,
+    Messages,
+    ToolCalls,
+    /// Estimated USD
+    Cost,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeseriesBucket {
+    #[serde(rename = "1m")]
+    Minute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "1h")]
+    Hour,
+    #[serde(rename = "1d")]
+    Day,
+}
+
+/// Either bound may be RFC 3339 or a local `YYYY-MM-DD`; a date as `to`
+/// includes that whole day.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimeRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timeseries {
+    pub metric: TimeseriesMetric,
+    pub bucket: TimeseriesBucket,
+    pub from: String,
+    pub to: String,
+    /// Every bucket in the range, oldest first, including empty ones
+    pub points: Vec<TimeseriesPoint>,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeseriesPoint {
+    /// Local start of the bucket, RFC 3339
+    pub start: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
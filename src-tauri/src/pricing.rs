@@ -0,0 +1,85 @@
+/// List prices in USD per million tokens. Cache writes cost 1.25x input for
+/// the 5-minute cache and 2x input for the 1-hour cache; cache reads cost
+/// 0.1x input.
+#[derive(Debug, Clone, Copy)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_write_1h: f64,
+    pub cache_read: f64,
+}
+
+impl ModelPrice {
+    const fn new(input: f64, output: f64) -> Self {
+        Self {
+            input,
+            output,
+            cache_write: input * 1.25,
+            cache_write_1h: input * 2.0,
+            cache_read: input * 0.1,
+        }
+    }
+}
+
+const OPUS_LEGACY: ModelPrice = ModelPrice::new(15.0, 75.0);
+const OPUS: ModelPrice = ModelPrice::new(5.0, 25.0);
+const SONNET: ModelPrice = ModelPrice::new(3.0, 15.0);
+const HAIKU: ModelPrice = ModelPrice::new(1.0, 5.0);
+const HAIKU_3_5: ModelPrice = ModelPrice::new(0.8, 4.0);
+const HAIKU_3: ModelPrice = ModelPrice::new(0.25, 1.25);
+
+/// Price for a model id such as `claude-sonnet-4-5-20250929`. Returns `None`
+/// for ids that aren't Claude models (e.g. `<synthetic>`).
+pub fn price_for(model: &str) -> Option<ModelPrice> {
+    let info = crate::model_mix::normalize(model);
+    let version = info.version.as_deref();
+    match info.family {
+        // Opus 3, 4 and 4.1 kept the original pricing; 4.5 onwards is cheaper.
+        // The dated snapshot `claude-opus-4-20250514` is version 4.
+        "opus" if matches!(version, Some("3" | "4" | "4.0" | "4.1")) => Some(OPUS_LEGACY),
+        "opus" => Some(OPUS),
+        "sonnet" => Some(SONNET),
+        "haiku" => Some(match version {
+            Some("3.5") => HAIKU_3_5,
+            Some("3") => HAIKU_3,
+            _ => HAIKU,
+        }),
+        _ => None,
+    }
+}
+
+/// Splits cache writes into 5-minute and 1-hour writes. Writes without a
+/// lifetime breakdown are taken as 5-minute writes.
+pub fn split_cache_writes(
+    cache_creation_input_tokens: u64,
+    cache_creation_1h_tokens: u64,
+) -> (u64, u64) {
+    let writes_1h = cache_creation_1h_tokens.min(cache_creation_input_tokens);
+    (cache_creation_input_tokens - writes_1h, writes_1h)
+}
+
+/// Estimated API-equivalent cost in USD for a set of token counts.
+/// `cache_creation_1h_tokens` is the part of the cache writes that went to
+/// the 1-hour cache.
+pub fn estimate_cost(
+    model: &str,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_read_input_tokens: u64,
+    cache_creation_input_tokens: u64,
+    cache_creation_1h_tokens: u64,
+) -> f64 {
+    let Some(price) = price_for(model) else {
+        return 0.0;
+    };
+    let (writes_5m, writes_1h) =
+        split_cache_writes(cache_creation_input_tokens, cache_creation_1h_tokens);
+
+    (input_tokens as f64 * price.input
+        + output_tokens as f64 * price.output
+        + cache_read_input_tokens as f64 * price.cache_read
+        + writes_5m as f64 * price.cache_write
+        + writes_1h as f64 * price.cache_write_1h)
+        / 1_000_000.0
+}
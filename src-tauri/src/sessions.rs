@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::commands::claude_dir;
+use crate::models::{
+    HistoryEntry, SessionSummary, TranscriptBlock, TranscriptMessage, TranscriptPage, TurnUsage,
+};
+use crate::pricing;
+
+const TRANSCRIPT_PAGE_SIZE: usize = 100;
+const MAX_TRANSCRIPT_PAGE_SIZE: usize = 500;
+/// Tool output (file dumps, test logs) is cut to keep pages small
+const MAX_TOOL_RESULT_CHARS: usize = 10_000;
+
+/// Per-session usage summaries, cached against each transcript's size and
+/// modification time so history pages only re-read sessions that changed.
+#[derive(Default)]
+pub struct SessionIndex {
+    inner: Mutex<HashMap<PathBuf, CachedSummary>>,
+}
+
+struct CachedSummary {
+    len: u64,
+    modified: Option<SystemTime>,
+    summary: SessionSummary,
+}
+
+impl SessionIndex {
+    /// Attaches the session summary to every entry that has a session id.
+    pub fn enrich(&self, entries: &mut [HistoryEntry]) {
+        let mut resolved: HashMap<String, Option<SessionSummary>> = HashMap::new();
+
+        for entry in entries.iter_mut() {
+            let Some(session_id) = entry.session_id.clone() else {
+                continue;
+            };
+            entry.session = resolved
+                .entry(session_id)
+                .or_insert_with_key(|session_id| {
+                    let path = find_transcript(entry.project.as_deref(), session_id)?;
+                    self.summary(&path)
+                        .map_err(|e| eprintln!("session summary error: {e}"))
+                        .ok()
+                })
+                .clone();
+        }
+    }
+
+    pub fn summary(&self, path: &Path) -> Result<SessionSummary, String> {
+        let metadata =
+            fs::metadata(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+        // Subagents keep writing their own files while the session's is idle
+        let subagent_paths = subagent_transcripts(path);
+        let mut len = metadata.len();
+        let mut modified = metadata.modified().ok();
+        for metadata in subagent_paths.iter().filter_map(|p| fs::metadata(p).ok()) {
+            len += metadata.len();
+            modified = modified.max(metadata.modified().ok());
+        }
+
+        let mut cache = self.inner.lock().unwrap();
+        if let Some(cached) = cache.get(path) {
+            if cached.len == len && cached.modified == modified {
+                return Ok(cached.summary.clone());
+            }
+        }
+
+        let summary = summarize(path, &subagent_paths)?;
+        cache.insert(
+            path.to_path_buf(),
+            CachedSummary {
+                len,
+                modified,
+                summary: summary.clone(),
+            },
+        );
+        Ok(summary)
+    }
+}
+
+/// Locates `<session_id>.jsonl` under `~/.claude/projects`. Claude Code names
+/// each project directory after its path with every non-alphanumeric
+/// character replaced by `-`; other directories are searched if that misses.
+pub fn find_transcript(project: Option<&str>, session_id: &str) -> Option<PathBuf> {
+    let projects_dir = claude_dir().join("projects");
+    let file_name = format!("{session_id}.jsonl");
+
+    if let Some(project) = project {
+        let encoded: String = project
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let path = projects_dir.join(encoded).join(&file_name);
+        if path.is_file() {
+            return Some(path);
+        }
+    }
+
+    fs::read_dir(projects_dir)
+        .ok()?
+        .flatten()
+        .map(|project_entry| project_entry.path().join(&file_name))
+        .find(|path| path.is_file())
+}
+
+/// Subagent transcripts Claude Code keeps under `<project>/<session-id>/`.
+fn subagent_transcripts(path: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let (Some(parent), Some(stem)) = (path.parent(), path.file_stem()) {
+        crate::commands::collect_transcripts(&parent.join(stem), &mut paths);
+    }
+    paths
+}
+
+/// Sums a session's transcript and its subagents' transcripts. Subagent
+/// traffic counts towards the totals and is also reported on its own.
+fn summarize(path: &Path, subagent_paths: &[PathBuf]) -> Result<SessionSummary, String> {
+    let mut paths = vec![path.to_path_buf()];
+    paths.extend_from_slice(subagent_paths);
+    let scan = crate::usage::scan_files(&paths);
+
+    let first = scan.messages().map(|(_, at)| at).min();
+    let last = scan.messages().map(|(_, at)| at).max();
+    let subagent_messages = scan.user_messages.iter().filter(|m| m.sidechain).count()
+        + scan.records.iter().filter(|r| r.sidechain).count();
+    let mut summary = SessionSummary {
+        session_id: path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string(),
+        message_count: (scan.messages().count() - subagent_messages) as u64,
+        duration_ms: match (first, last) {
+            (Some(first), Some(last)) => (last - first).num_milliseconds().max(0) as u64,
+            _ => 0,
+        },
+        started_at: first.map(|at| at.to_rfc3339()),
+        ended_at: last.map(|at| at.to_rfc3339()),
+        subagent_message_count: subagent_messages as u64,
+        ..Default::default()
+    };
+
+    let mut tokens_by_model: HashMap<&str, u64> = HashMap::new();
+    for record in &scan.records {
+        let cost = record.cost_usd();
+        summary.input_tokens += record.input_tokens;
+        summary.output_tokens += record.output_tokens;
+        summary.cache_read_input_tokens += record.cache_read_input_tokens;
+        summary.cache_creation_input_tokens += record.cache_creation_input_tokens;
+        summary.cost_usd += cost;
+        if record.sidechain {
+            summary.subagent_tokens += record.total_tokens();
+            summary.subagent_cost_usd += cost;
+        }
+
+        if pricing::price_for(&record.model).is_some() {
+            *tokens_by_model.entry(&record.model).or_insert(0) +=
+                record.input_tokens + record.output_tokens;
+        }
+    }
+
+    let mut models: Vec<(&str, u64)> = tokens_by_model.into_iter().collect();
+    models.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    summary.models = models
+        .into_iter()
+        .map(|(model, _)| model.to_string())
+        .collect();
+
+    Ok(summary)
+}
+
+/// Parses a session transcript into conversation messages. Assistant replies
+/// are written one content block per line under the same message id; those
+/// lines are merged back into a single message. `offset` and `limit` count
+/// merged messages, and only the requested page is materialised.
+#[allow(clippy::lines_filter_map_ok)]
+pub fn transcript(
+    path: &Path,
+    offset: usize,
+    limit: Option<usize>,
+) -> Result<TranscriptPage, String> {
+    let limit = limit
+        .unwrap_or(TRANSCRIPT_PAGE_SIZE)
+        .clamp(1, MAX_TRANSCRIPT_PAGE_SIZE);
+    let file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let reader = BufReader::new(file);
+
+    let mut messages: Vec<TranscriptMessage> = Vec::new();
+    let mut total = 0usize;
+    let mut open_assistant_id: Option<String> = None;
+
+    for line in reader.lines().flatten() {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        let role = match value.get("type").and_then(|t| t.as_str()) {
+            Some(role @ ("user" | "assistant")) => role,
+            _ => continue,
+        };
+        // Command caveats and other injected context aren't part of the conversation
+        if value.get("isMeta").and_then(|m| m.as_bool()) == Some(true) {
+            continue;
+        }
+
+        let message = value.get("message").unwrap_or(&serde_json::Value::Null);
+        let in_page = |index: usize| index >= offset && index < offset + limit;
+
+        if role == "assistant" {
+            let message_id = message
+                .get("id")
+                .and_then(|id| id.as_str())
+                .map(ToString::to_string);
+            if message_id.is_some() && message_id == open_assistant_id {
+                if in_page(total - 1) {
+                    if let Some(last) = messages.last_mut() {
+                        merge_assistant_line(last, message);
+                    }
+                }
+                continue;
+            }
+            open_assistant_id = message_id;
+        } else {
+            open_assistant_id = None;
+        }
+
+        let index = total;
+        total += 1;
+        if !in_page(index) {
+            continue;
+        }
+
+        let mut parsed = TranscriptMessage {
+            uuid: value
+                .get("uuid")
+                .and_then(|u| u.as_str())
+                .map(ToString::to_string),
+            role: role.to_string(),
+            timestamp: value
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .map(ToString::to_string),
+            model: None,
+            is_sidechain: value
+                .get("isSidechain")
+                .and_then(|s| s.as_bool())
+                .unwrap_or(false),
+            blocks: Vec::new(),
+            usage: None,
+        };
+        if role == "assistant" {
+            merge_assistant_line(&mut parsed, message);
+        } else {
+            parsed.blocks = content_blocks(message.get("content"));
+        }
+        messages.push(parsed);
+    }
+
+    Ok(TranscriptPage {
+        session_id: path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string(),
+        messages,
+        total,
+        next_offset: (offset + limit < total).then_some(offset + limit),
+    })
+}
+
+fn merge_assistant_line(parsed: &mut TranscriptMessage, message: &serde_json::Value) {
+    if let Some(model) = message.get("model").and_then(|m| m.as_str()) {
+        if !model.is_empty() {
+            parsed.model = Some(model.to_string());
+        }
+    }
+    parsed.blocks.extend(content_blocks(message.get("content")));
+
+    if let Some(usage) = message.get("usage") {
+        let tokens = |key: &str| usage.get(key).and_then(|t| t.as_u64()).unwrap_or(0);
+        let turn = parsed.usage.get_or_insert_with(TurnUsage::default);
+        turn.input_tokens = turn.input_tokens.max(tokens("input_tokens"));
+        turn.output_tokens = turn.output_tokens.max(tokens("output_tokens"));
+        turn.cache_read_input_tokens = turn
+            .cache_read_input_tokens
+            .max(tokens("cache_read_input_tokens"));
+        turn.cache_creation_input_tokens = turn
+            .cache_creation_input_tokens
+            .max(tokens("cache_creation_input_tokens"));
+    }
+}
+
+fn content_blocks(content: Option<&serde_json::Value>) -> Vec<TranscriptBlock> {
+    match content {
+        Some(serde_json::Value::String(text)) => vec![TranscriptBlock::Text { text: text.clone() }],
+        Some(serde_json::Value::Array(blocks)) => blocks.iter().filter_map(content_block).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn content_block(block: &serde_json::Value) -> Option<TranscriptBlock> {
+    let text_field = |key: &str| {
+        block
+            .get(key)
+            .and_then(|t| t.as_str())
+            .map(ToString::to_string)
+    };
+
+    match block.get("type").and_then(|t| t.as_str())? {
+        "text" => Some(TranscriptBlock::Text {
+            text: text_field("text")?,
+        }),
+        "thinking" => Some(TranscriptBlock::Thinking {
+            text: text_field("thinking")?,
+        }),
+        "tool_use" | "server_tool_use" => Some(TranscriptBlock::ToolUse {
+            id: text_field("id"),
+            name: text_field("name").unwrap_or_default(),
+            input: block.get("input").cloned().unwrap_or_default(),
+        }),
+        "tool_result" => {
+            let (content, truncated) = truncate_chars(tool_result_text(block.get("content")));
+            Some(TranscriptBlock::ToolResult {
+                tool_use_id: text_field("tool_use_id"),
+                content,
+                is_error: block
+                    .get("is_error")
+                    .and_then(|e| e.as_bool())
+                    .unwrap_or(false),
+                truncated,
+            })
+        }
+        "image" => Some(TranscriptBlock::Image {
+            media_type: block
+                .get("source")
+                .and_then(|s| s.get("media_type"))
+                .and_then(|m| m.as_str())
+                .map(ToString::to_string),
+        }),
+        _ => None,
+    }
+}
+
+/// Tool results are either a string or a list of text/image blocks.
+fn tool_result_text(content: Option<&serde_json::Value>) -> String {
+    match content {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(serde_json::Value::Array(blocks)) => blocks
+            .iter()
+            .map(|block| match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => block
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                Some("image") => "[image]".to_string(),
+                _ => String::new(),
+            })
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn truncate_chars(text: String) -> (String, bool) {
+    match text.char_indices().nth(MAX_TOOL_RESULT_CHARS) {
+        Some((end, _)) => (text[..end].to_string(), true),
+        None => (text, false),
+    }
+}
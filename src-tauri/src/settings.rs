@@ -0,0 +1,29 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::models::Settings;
+
+fn settings_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("com.brewstatus").join("settings.json"))
+}
+
+/// Reads persisted settings, falling back to defaults for a missing or
+/// unreadable file so a bad write never locks the user out of the app.
+pub fn load() -> Settings {
+    settings_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(settings: &Settings) -> Result<(), String> {
+    let path = settings_path().ok_or_else(|| "Could not find data directory".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {e}"))?;
+    }
+
+    let data = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {e}"))?;
+    fs::write(&path, data).map_err(|e| format!("Failed to write settings: {e}"))
+}
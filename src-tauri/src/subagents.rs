@@ -0,0 +1,84 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::models::{SubagentDay, SubagentSession, SubagentUsage, TimeRange, UsageTotals};
+use crate::usage::{percent, UsageRecord};
+
+impl UsageTotals {
+    fn add(&mut self, record: &UsageRecord) {
+        self.replies += 1;
+        self.total_tokens += record.total_tokens();
+        self.cost_usd += record.cost_usd();
+    }
+}
+
+/// Main conversation vs subagent (sidechain) usage over the range, overall,
+/// per parent session and per day.
+pub fn usage(range: &TimeRange) -> Result<SubagentUsage, String> {
+    let (from, to, scan) = crate::usage::scan_range(range)?;
+
+    let mut main = UsageTotals::default();
+    let mut subagents = UsageTotals::default();
+    let mut sessions: HashMap<String, SubagentSession> = HashMap::new();
+    let mut agents: HashMap<String, HashSet<&str>> = HashMap::new();
+    let mut daily: BTreeMap<String, SubagentDay> = BTreeMap::new();
+    for record in &scan.records {
+        let date = record.timestamp.format("%Y-%m-%d").to_string();
+        let day = daily.entry(date.clone()).or_insert_with(|| SubagentDay {
+            date,
+            ..Default::default()
+        });
+        let session = sessions
+            .entry(record.session_id.clone())
+            .or_insert_with(|| SubagentSession {
+                session_id: record.session_id.clone(),
+                project: record.project.clone(),
+                ..Default::default()
+            });
+
+        if record.sidechain {
+            subagents.add(record);
+            session.subagents.add(record);
+            day.subagent_tokens += record.total_tokens();
+            if let Some(agent_id) = &record.agent_id {
+                agents
+                    .entry(record.session_id.clone())
+                    .or_default()
+                    .insert(agent_id);
+            }
+        } else {
+            main.add(record);
+            session.main.add(record);
+            day.main_tokens += record.total_tokens();
+        }
+    }
+
+    let mut sessions: Vec<SubagentSession> = sessions
+        .into_values()
+        .filter(|s| s.subagents.replies > 0)
+        .map(|mut session| {
+            session.agents = agents
+                .get(&session.session_id)
+                .map_or(0, |a| a.len() as u64);
+            session
+        })
+        .collect();
+    sessions.sort_by(|a, b| {
+        b.subagents
+            .total_tokens
+            .cmp(&a.subagents.total_tokens)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+
+    Ok(SubagentUsage {
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        subagent_share: percent(
+            subagents.total_tokens,
+            main.total_tokens + subagents.total_tokens,
+        ),
+        main,
+        subagents,
+        sessions,
+        daily: daily.into_values().collect(),
+    })
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Days, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Timelike};
+
+use crate::models::{
+    TimeRange, Timeseries, TimeseriesBucket, TimeseriesMetric, TimeseriesPoint, WeeklyPattern,
+};
+use crate::usage::UsageScan;
+
+/// Enough for a week of minutes or a year of hours
+const MAX_POINTS: i64 = 10_080;
+const DEFAULT_RANGE: TimeDelta = TimeDelta::hours(24);
+const DEFAULT_PATTERN_RANGE: TimeDelta = TimeDelta::weeks(4);
+
+impl TimeseriesBucket {
+    fn floor(self, at: NaiveDateTime) -> NaiveDateTime {
+        let minute = match self {
+            TimeseriesBucket::Minute => at.minute(),
+            TimeseriesBucket::FiveMinutes => at.minute() - at.minute() % 5,
+            TimeseriesBucket::Hour | TimeseriesBucket::Day => 0,
+        };
+        let hour = match self {
+            TimeseriesBucket::Day => 0,
+            _ => at.hour(),
+        };
+        at.date().and_hms_opt(hour, minute, 0).unwrap_or(at)
+    }
+
+    /// Start of the bucket after the one starting at `start`. Days step by
+    /// calendar day so they stay aligned to local midnight across DST.
+    fn next(self, start: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            TimeseriesBucket::Minute => start + TimeDelta::minutes(1),
+            TimeseriesBucket::FiveMinutes => start + TimeDelta::minutes(5),
+            TimeseriesBucket::Hour => start + TimeDelta::hours(1),
+            TimeseriesBucket::Day => start + Days::new(1),
+        }
+    }
+
+    fn approx_length(self) -> TimeDelta {
+        match self {
+            TimeseriesBucket::Minute => TimeDelta::minutes(1),
+            TimeseriesBucket::FiveMinutes => TimeDelta::minutes(5),
+            TimeseriesBucket::Hour => TimeDelta::hours(1),
+            TimeseriesBucket::Day => TimeDelta::days(1),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TimeseriesBucket::Minute => "1m",
+            TimeseriesBucket::FiveMinutes => "5m",
+            TimeseriesBucket::Hour => "1h",
+            TimeseriesBucket::Day => "1d",
+        }
+    }
+}
+
+/// Sums `metric` per `bucket` over `[range.from, range.to)`, from the
+/// transcripts rather than the stats cache, which only keeps daily totals and
+/// hour-of-day counts.
+pub fn build(
+    metric: TimeseriesMetric,
+    bucket: TimeseriesBucket,
+    range: &TimeRange,
+) -> Result<Timeseries, String> {
+    let (from, to) = resolve_range(range, DEFAULT_RANGE)?;
+    if (to - from).num_seconds() / bucket.approx_length().num_seconds() > MAX_POINTS {
+        return Err(format!(
+            "Range is too long for {} buckets (at most {MAX_POINTS} points)",
+            bucket.label()
+        ));
+    }
+
+    let mut values: HashMap<NaiveDateTime, f64> = HashMap::new();
+    let scan = crate::usage::scan_between(from, to);
+    for (at, value) in weighted(&scan, metric) {
+        *values.entry(bucket.floor(at.naive_local())).or_insert(0.0) += value;
+    }
+
+    let mut points = Vec::new();
+    let end = to.naive_local();
+    let mut start = bucket.floor(from.naive_local());
+    while start < end {
+        // Local times skipped by a DST change have no bucket
+        if let Some(at) = Local.from_local_datetime(&start).earliest() {
+            points.push(TimeseriesPoint {
+                start: at.to_rfc3339(),
+                value: values.get(&start).copied().unwrap_or(0.0),
+            });
+        }
+        start = bucket.next(start);
+    }
+
+    Ok(Timeseries {
+        metric,
+        bucket,
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        total: points.iter().fold(0.0, |sum, p| sum + p.value),
+        points,
+    })
+}
+
+/// Sums `metric` by local weekday and hour of day over the range, which
+/// defaults to the last four weeks. Messages are counted as in the stats
+/// cache's `hour_counts`.
+pub fn weekly_pattern(
+    metric: TimeseriesMetric,
+    range: &TimeRange,
+) -> Result<WeeklyPattern, String> {
+    let (from, to) = resolve_range(range, DEFAULT_PATTERN_RANGE)?;
+    let cells = crate::commands::weekday_hour_grid(&crate::usage::scan_between(from, to), metric);
+
+    Ok(WeeklyPattern {
+        metric,
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        max: cells.iter().flatten().fold(0.0, |max: f64, v| max.max(*v)),
+        total: cells.iter().flatten().fold(0.0, |sum, v| sum + v),
+        cells,
+    })
+}
+
+/// Timestamp and `metric` value of each assistant reply, or of each message
+/// (user lines included, as the stats cache counts them) for `Messages`.
+pub(crate) fn weighted(scan: &UsageScan, metric: TimeseriesMetric) -> Vec<(DateTime<Local>, f64)> {
+    if metric == TimeseriesMetric::Messages {
+        return scan.messages().map(|(_, at)| (at, 1.0)).collect();
+    }
+    scan.records
+        .iter()
+        .map(|record| {
+            let value = match metric {
+                TimeseriesMetric::Tokens => record.total_tokens() as f64,
+                TimeseriesMetric::ToolCalls => record.tool_uses.len() as f64,
+                TimeseriesMetric::Cost => record.cost_usd(),
+                TimeseriesMetric::Messages => 1.0,
+            };
+            (record.timestamp, value)
+        })
+        .collect()
+}
+
+/// Without `from`, the range covers `default_length` up to `to`, which
+/// defaults to now.
+pub fn resolve_range(
+    range: &TimeRange,
+    default_length: TimeDelta,
+) -> Result<(DateTime<Local>, DateTime<Local>), String> {
+    let to = parse_bound(range.to.as_deref(), true)?.unwrap_or_else(Local::now);
+    let from = parse_bound(range.from.as_deref(), false)?.unwrap_or(to - default_length);
+    if from >= to {
+        return Err("The range must start before it ends".to_string());
+    }
+    Ok((from, to))
+}
+
+/// A plain date means the start of that day, or for the end of a range, the
+/// start of the next one so the day is included.
+fn parse_bound(value: Option<&str>, end: bool) -> Result<Option<DateTime<Local>>, String> {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Some(at.with_timezone(&Local)));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date or time '{value}'"))?;
+    let date = if end { date + Days::new(1) } else { date };
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+        .map(Some)
+        .ok_or_else(|| format!("Invalid date '{value}'"))
+}
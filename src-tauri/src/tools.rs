@@ -0,0 +1,160 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::models::{
+    McpServerStat, McpToolStat, McpUsage, TimeRange, ToolBreakdown, ToolStat, ToolUsage,
+};
+use crate::usage::percent;
+
+impl ToolBreakdown {
+    fn add(&mut self, tool: &str, failed: bool) {
+        self.calls += 1;
+        self.errors += u64::from(failed);
+        *self.tools.entry(tool.to_string()).or_insert(0) += 1;
+    }
+}
+
+/// Splits `mcp__<server>__<tool>` into the server and tool names.
+fn mcp_parts(name: &str) -> Option<(&str, &str)> {
+    name.strip_prefix("mcp__")?.split_once("__")
+}
+
+/// Tool calls per tool, day and project over the range, with the share that
+/// returned an error.
+pub fn usage(range: &TimeRange) -> Result<ToolUsage, String> {
+    let (from, to, scan) = crate::usage::scan_range(range)?;
+
+    let mut tools: HashMap<String, ToolStat> = HashMap::new();
+    let mut daily: BTreeMap<String, ToolBreakdown> = BTreeMap::new();
+    let mut projects: HashMap<String, ToolBreakdown> = HashMap::new();
+    for record in &scan.records {
+        let date = record.timestamp.format("%Y-%m-%d").to_string();
+        for tool_use in &record.tool_uses {
+            let failed = scan
+                .tool_results
+                .get(&tool_use.id)
+                .is_some_and(|r| r.is_error);
+            let server = mcp_parts(&tool_use.name).map(|(server, _)| server);
+            let name = match server {
+                Some(server) => format!("mcp__{server}"),
+                None if tool_use.name.is_empty() => "Unknown".to_string(),
+                None => tool_use.name.clone(),
+            };
+
+            let stat = tools.entry(name.clone()).or_insert_with(|| ToolStat {
+                name: name.clone(),
+                mcp_server: server.map(ToString::to_string),
+                ..Default::default()
+            });
+            stat.calls += 1;
+            stat.errors += u64::from(failed);
+
+            daily
+                .entry(date.clone())
+                .or_insert_with(|| breakdown(&date))
+                .add(&name, failed);
+            projects
+                .entry(record.project.clone())
+                .or_insert_with(|| breakdown(&record.project))
+                .add(&name, failed);
+        }
+    }
+
+    let mut tools: Vec<ToolStat> = tools
+        .into_values()
+        .map(|mut stat| {
+            stat.error_rate = percent(stat.errors, stat.calls);
+            stat
+        })
+        .collect();
+    tools.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.name.cmp(&b.name)));
+    let mut projects: Vec<ToolBreakdown> = projects.into_values().collect();
+    projects.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.key.cmp(&b.key)));
+
+    let calls = tools.iter().map(|t| t.calls).sum();
+    let errors = tools.iter().map(|t| t.errors).sum();
+    Ok(ToolUsage {
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        calls,
+        errors,
+        error_rate: percent(errors, calls),
+        tools,
+        daily: daily.into_values().collect(),
+        projects,
+    })
+}
+
+/// Calls, result sizes and turn tokens per MCP server over the range, to
+/// weigh what each server adds to the context.
+pub fn mcp_usage(range: &TimeRange) -> Result<McpUsage, String> {
+    let (from, to, scan) = crate::usage::scan_range(range)?;
+
+    let mut servers: HashMap<String, McpServerStat> = HashMap::new();
+    let mut tools: HashMap<String, HashMap<String, McpToolStat>> = HashMap::new();
+    for record in &scan.records {
+        let mut called: HashSet<&str> = HashSet::new();
+        for tool_use in &record.tool_uses {
+            let Some((server, tool)) = mcp_parts(&tool_use.name) else {
+                continue;
+            };
+            let result = scan.tool_results.get(&tool_use.id);
+            let failed = result.is_some_and(|r| r.is_error);
+            let size = result.map_or(0, |r| r.size);
+
+            let stat = servers
+                .entry(server.to_string())
+                .or_insert_with(|| McpServerStat {
+                    server: server.to_string(),
+                    ..Default::default()
+                });
+            stat.calls += 1;
+            stat.errors += u64::from(failed);
+            stat.result_bytes += size;
+            if called.insert(server) {
+                stat.turns += 1;
+                stat.turn_tokens += record.total_tokens();
+                stat.turn_cost_usd += record.cost_usd();
+            }
+
+            let tool_stat = tools
+                .entry(server.to_string())
+                .or_default()
+                .entry(tool.to_string())
+                .or_insert_with(|| McpToolStat {
+                    name: tool.to_string(),
+                    ..Default::default()
+                });
+            tool_stat.calls += 1;
+            tool_stat.errors += u64::from(failed);
+            tool_stat.result_bytes += size;
+        }
+    }
+
+    let mut servers: Vec<McpServerStat> = servers
+        .into_values()
+        .map(|mut stat| {
+            let mut server_tools: Vec<McpToolStat> = tools
+                .remove(&stat.server)
+                .unwrap_or_default()
+                .into_values()
+                .collect();
+            server_tools.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.name.cmp(&b.name)));
+            stat.tools = server_tools;
+            stat
+        })
+        .collect();
+    servers.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.server.cmp(&b.server)));
+
+    Ok(McpUsage {
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        servers,
+    })
+}
+
+fn breakdown(key: &str) -> ToolBreakdown {
+    ToolBreakdown {
+        key: key.to_string(),
+        ..Default::default()
+    }
+}
@@ -1,38 +1,99 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use tauri::{
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItem, MenuItemBuilder},
     tray::TrayIconBuilder,
-    AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+    AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, Wry,
 };
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_positioner::{Position, WindowExt};
 
-pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
-    let open_item = MenuItemBuilder::with_id("open", "Open App").build(app)?;
-    let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
-    let menu = MenuBuilder::new(app)
-        .item(&open_item)
-        .separator()
-        .item(&quit_item)
-        .build()?;
+use crate::commands::claude_dir;
+use crate::models::{BudgetLevel, LimitEntry, TodaySummary, UsageLimits};
 
-    #[cfg(target_os = "macos")]
-    let icon = Image::from_bytes(include_bytes!("../icons/trayTemplate.png"))
-        .expect("Failed to load tray icon");
-    #[cfg(not(target_os = "macos"))]
-    let icon =
-        Image::from_bytes(include_bytes!("../icons/32x32.png")).expect("Failed to load tray icon");
+const TRAY_ID: &str = "main";
+const DEFAULT_TOOLTIP: &str = "Brew Status - Claude Code Usage";
+const USAGE_POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const MENU_REFRESH_DEBOUNCE: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+struct TrayState {
+    limits: Mutex<Option<UsageLimits>>,
+    today: Mutex<Option<TodaySummary>>,
+    recent_project: Mutex<Option<String>>,
+    refresh_pending: AtomicBool,
+}
+
+pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
+    app.manage(TrayState::default());
+    let menu = build_menu(app)?;
 
-    let tray_builder = TrayIconBuilder::new()
-        .icon(icon)
-        .tooltip("Brew Status - Claude Code Usage")
+    let tray_builder = TrayIconBuilder::with_id(TRAY_ID)
+        .icon(base_icon())
+        .tooltip(DEFAULT_TOOLTIP)
         .show_menu_on_left_click(false)
         .menu(&menu)
         .on_menu_event(|app, event| match event.id().as_ref() {
             "open" => {
                 show_main_window(app);
             }
+            "popup" => {
+                toggle_popup(app);
+            }
+            "refresh" => {
+                refresh_now(app);
+            }
+            "copy_summary" => {
+                copy_today_summary(app);
+            }
+            "open_claude_dir" => {
+                let _ = app
+                    .opener()
+                    .open_path(claude_dir().to_string_lossy(), None::<&str>);
+            }
+            "open_recent_project" => {
+                let project = app
+                    .state::<TrayState>()
+                    .recent_project
+                    .lock()
+                    .unwrap()
+                    .clone();
+                if let Some(project) = project {
+                    let _ = app.opener().open_path(project, None::<&str>);
+                }
+            }
+            "check_updates" => {
+                check_updates_from_menu(app);
+            }
+            "notifications" => {
+                let mut settings = crate::settings::load();
+                settings.notifications_enabled = !settings.notifications_enabled;
+                if let Err(e) = crate::settings::save(&settings) {
+                    eprintln!("{e}");
+                }
+                rebuild_menu(app);
+                let _ = app.emit("settings-updated", ());
+            }
+            "launch_at_login" => {
+                let mut settings = crate::settings::load();
+                settings.launch_at_login = !settings.launch_at_login;
+                match crate::autostart::sync(settings.launch_at_login) {
+                    Ok(()) => {
+                        if let Err(e) = crate::settings::save(&settings) {
+                            eprintln!("{e}");
+                        }
+                    }
+                    Err(e) => eprintln!("launch at login error: {e}"),
+                }
+                rebuild_menu(app);
+                let _ = app.emit("settings-updated", ());
+            }
             "quit" => {
                 app.exit(0);
             }
@@ -51,9 +112,485 @@ pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
 
     tray_builder.build(app)?;
 
+    schedule_menu_refresh(app);
+
     Ok(())
 }
 
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let state = app.state::<TrayState>();
+    let limits = state.limits.lock().unwrap().clone();
+    let today = state.today.lock().unwrap().clone();
+    let recent_project = state.recent_project.lock().unwrap().clone();
+    let settings = crate::settings::load();
+
+    let mut builder = MenuBuilder::new(app);
+
+    let today_label = match &today {
+        Some(today) => format!(
+            "Today: {} tokens \u{00B7} {} messages",
+            format_token_count(today.total_tokens),
+            today.messages
+        ),
+        None => "Today: loading...".to_string(),
+    };
+    builder = builder.item(&stat_item(app, "stat_today", today_label)?);
+
+    if let Some(limits) = &limits {
+        if let Some(entry) = &limits.five_hour {
+            let label = format!("Session limit: {}%", entry.utilization.round() as i64);
+            builder = builder.item(&stat_item(app, "stat_session", label)?);
+        }
+        if let Some(entry) = &limits.seven_day {
+            let label = format!("Weekly limit: {}%", entry.utilization.round() as i64);
+            builder = builder.item(&stat_item(app, "stat_weekly", label)?);
+        }
+        if let Some((name, countdown)) = next_reset(limits) {
+            let label = format!("Next reset: {name} in {countdown}");
+            builder = builder.item(&stat_item(app, "stat_reset", label)?);
+        }
+    }
+
+    for (index, status) in crate::budgets::latest(app)
+        .iter()
+        .filter(|status| status.level != BudgetLevel::Ok)
+        .enumerate()
+    {
+        let label = crate::budgets::menu_label(status);
+        builder = builder.item(&stat_item(app, &format!("stat_budget_{index}"), label)?);
+    }
+
+    let recent_label = match recent_project.as_deref().map(crate::history::project_name) {
+        Some(name) => format!("Open Recent Project ({name})"),
+        None => "Open Recent Project".to_string(),
+    };
+
+    builder = builder.separator();
+
+    // AppIndicator trays don't deliver icon clicks, so the popup needs a menu entry
+    #[cfg(target_os = "linux")]
+    {
+        builder = builder.item(&MenuItemBuilder::with_id("popup", "Show Usage").build(app)?);
+    }
+
+    builder = builder
+        .item(&MenuItemBuilder::with_id("open", "Open App").build(app)?)
+        .item(&MenuItemBuilder::with_id("refresh", "Refresh Now").build(app)?)
+        .item(
+            &MenuItemBuilder::with_id("copy_summary", "Copy Today's Summary")
+                .enabled(today.is_some())
+                .build(app)?,
+        )
+        .item(&MenuItemBuilder::with_id("open_claude_dir", "Open ~/.claude Folder").build(app)?)
+        .item(
+            &MenuItemBuilder::with_id("open_recent_project", recent_label)
+                .enabled(recent_project.is_some())
+                .build(app)?,
+        )
+        .separator()
+        .item(&MenuItemBuilder::with_id("check_updates", "Check for Updates").build(app)?)
+        .item(
+            &CheckMenuItemBuilder::with_id("notifications", "Notifications")
+                .checked(settings.notifications_enabled)
+                .build(app)?,
+        )
+        .item(
+            &CheckMenuItemBuilder::with_id("launch_at_login", "Launch at Login")
+                .checked(settings.launch_at_login)
+                .build(app)?,
+        )
+        .separator()
+        .item(&MenuItemBuilder::with_id("quit", "Quit").build(app)?);
+
+    builder.build()
+}
+
+fn stat_item(app: &AppHandle, id: &str, label: String) -> tauri::Result<MenuItem<Wry>> {
+    MenuItemBuilder::with_id(id, label)
+        .enabled(false)
+        .build(app)
+}
+
+pub fn rebuild_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("tray menu error: {e}"),
+    }
+}
+
+/// Recomputes today's stats for the tray menu. Session files change many times
+/// per second while Claude Code is working, so bursts of calls are coalesced
+/// into a single refresh.
+pub fn schedule_menu_refresh(app: &AppHandle) {
+    let state = app.state::<TrayState>();
+    if state.refresh_pending.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(MENU_REFRESH_DEBOUNCE);
+        let state = app.state::<TrayState>();
+        state.refresh_pending.store(false, Ordering::SeqCst);
+
+        match crate::commands::get_today_summary() {
+            Ok(summary) => *state.today.lock().unwrap() = Some(summary),
+            Err(e) => eprintln!("tray summary error: {e}"),
+        }
+        if let Ok(entries) = crate::commands::get_history(1) {
+            *state.recent_project.lock().unwrap() =
+                entries.into_iter().next().and_then(|entry| entry.project);
+        }
+
+        rebuild_menu(&app);
+    });
+}
+
+fn refresh_now(app: &AppHandle) {
+    schedule_menu_refresh(app);
+    let _ = app.emit("stats-updated", ());
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match crate::commands::fetch_usage_limits().await {
+            Ok(limits) => {
+                update_usage(&app, &limits);
+                let _ = app.emit("limits-updated", ());
+            }
+            Err(e) => eprintln!("usage refresh error: {e}"),
+        }
+    });
+}
+
+fn copy_today_summary(app: &AppHandle) {
+    let state = app.state::<TrayState>();
+    let Some(today) = state.today.lock().unwrap().clone() else {
+        return;
+    };
+    let limits = state.limits.lock().unwrap().clone();
+
+    let mut lines = vec![
+        format!("Claude Code usage for {}", today.date),
+        format!("Tokens: {}", format_token_count(today.total_tokens)),
+    ];
+
+    let mut models: Vec<(&String, &u64)> = today.tokens_by_model.iter().collect();
+    models.sort_by_key(|(_, tokens)| std::cmp::Reverse(**tokens));
+    for (model, tokens) in models {
+        lines.push(format!(
+            "  {}: {}",
+            model_display_name(model),
+            format_token_count(*tokens)
+        ));
+    }
+
+    lines.push(format!("Messages: {}", today.messages));
+    lines.push(format!("Sessions: {}", today.sessions));
+    lines.push(format!("Tool calls: {}", today.tool_calls));
+
+    if let Some(limits) = &limits {
+        if let Some(entry) = &limits.five_hour {
+            lines.push(format_limit_line("Session limit", entry));
+        }
+        if let Some(entry) = &limits.seven_day {
+            lines.push(format_limit_line("Weekly limit", entry));
+        }
+    }
+
+    if let Err(e) = app.clipboard().write_text(lines.join("\n")) {
+        eprintln!("clipboard error: {e}");
+    }
+}
+
+fn check_updates_from_menu(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let message = match crate::commands::check_for_updates(app.clone()).await {
+            // The install prompt is shown by check_for_updates itself
+            Ok(result) if result.update_available => return,
+            Ok(_) => "You're running the latest version of Brew Status.".to_string(),
+            Err(e) => e,
+        };
+        app.dialog()
+            .message(message)
+            .title("Check for Updates")
+            .kind(MessageDialogKind::Info)
+            .show(|_| {});
+    });
+}
+
+/// Returns the label and countdown of the limit that resets soonest.
+fn next_reset(limits: &UsageLimits) -> Option<(&'static str, String)> {
+    [
+        ("Session", &limits.five_hour),
+        ("Weekly", &limits.seven_day),
+        ("Sonnet", &limits.seven_day_sonnet),
+        ("Opus", &limits.seven_day_opus),
+    ]
+    .into_iter()
+    .filter_map(|(name, entry)| {
+        let resets_at = entry.as_ref()?.resets_at.as_deref()?;
+        let reset = DateTime::parse_from_rfc3339(resets_at).ok()?;
+        Some((name, reset, resets_at))
+    })
+    .min_by_key(|(_, reset, _)| *reset)
+    .and_then(|(name, _, resets_at)| Some((name, format_reset_countdown(resets_at)?)))
+}
+
+pub fn format_token_count(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.1}K", n as f64 / 1_000.0)
+    } else {
+        n.to_string()
+    }
+}
+
+fn model_display_name(model: &str) -> &str {
+    if model.contains("opus") {
+        "Opus"
+    } else if model.contains("sonnet") {
+        "Sonnet"
+    } else if model.contains("haiku") {
+        "Haiku"
+    } else {
+        model
+    }
+}
+
+fn base_icon() -> Image<'static> {
+    #[cfg(target_os = "macos")]
+    let icon = Image::from_bytes(include_bytes!("../icons/trayTemplate.png"))
+        .expect("Failed to load tray icon");
+    #[cfg(not(target_os = "macos"))]
+    let icon =
+        Image::from_bytes(include_bytes!("../icons/32x32.png")).expect("Failed to load tray icon");
+
+    icon
+}
+
+/// Polls the usage endpoint in the background so the tray stays current even
+/// when neither window is open.
+pub fn start_usage_poller(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match crate::commands::fetch_usage_limits().await {
+                Ok(limits) => update_usage(&app, &limits),
+                Err(e) => eprintln!("usage poll error: {e}"),
+            }
+            tokio::time::sleep(USAGE_POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Usage limits from the most recent poll, if any succeeded.
+pub fn latest_limits(app: &AppHandle) -> Option<UsageLimits> {
+    app.state::<TrayState>().limits.lock().unwrap().clone()
+}
+
+/// Refreshes the tray tooltip and icon from the latest usage limits.
+pub fn update_usage(app: &AppHandle, limits: &UsageLimits) {
+    *app.state::<TrayState>().limits.lock().unwrap() = Some(limits.clone());
+    rebuild_menu(app);
+    crate::alerts::check_limits(app, limits);
+    crate::digest::record_limit_peaks(app, limits);
+    if let Some(extra) = &limits.extra_usage {
+        crate::extra_usage::record(app, extra);
+    }
+    // Also picks up budget periods rolling over while Claude Code is idle
+    crate::budgets::schedule_refresh(app);
+
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+
+    let _ = tray.set_tooltip(Some(usage_tooltip(limits)));
+
+    let icon = match most_constrained_utilization(limits) {
+        Some(utilization) => render_usage_icon(utilization),
+        None => base_icon(),
+    };
+    let _ = tray.set_icon(Some(icon));
+
+    // AppIndicator ignores tooltips; show the headline figure as a label instead
+    #[cfg(target_os = "linux")]
+    let _ = tray
+        .set_title(most_constrained_utilization(limits).map(|u| format!("{}%", u.round() as i64)));
+
+    #[cfg(target_os = "macos")]
+    let _ = tray.set_icon_as_template(true);
+}
+
+fn usage_tooltip(limits: &UsageLimits) -> String {
+    let mut lines = vec!["Brew Status".to_string()];
+
+    if let Some(entry) = &limits.five_hour {
+        lines.push(format_limit_line("Session", entry));
+    }
+    if let Some(entry) = &limits.seven_day {
+        lines.push(format_limit_line("Weekly", entry));
+    }
+
+    if lines.len() == 1 {
+        return DEFAULT_TOOLTIP.to_string();
+    }
+    lines.join("\n")
+}
+
+fn format_limit_line(label: &str, entry: &LimitEntry) -> String {
+    let pct = entry.utilization.round() as i64;
+    match entry.resets_at.as_deref().and_then(format_reset_countdown) {
+        Some(countdown) => format!("{label}: {pct}% \u{00B7} resets in {countdown}"),
+        None => format!("{label}: {pct}%"),
+    }
+}
+
+/// Formats the time remaining until an RFC 3339 reset timestamp, e.g. `2h 13m`.
+pub fn format_reset_countdown(resets_at: &str) -> Option<String> {
+    let reset = DateTime::parse_from_rfc3339(resets_at).ok()?;
+    let remaining = reset.with_timezone(&Utc) - Utc::now();
+    let total_minutes = remaining.num_minutes();
+
+    if total_minutes <= 0 {
+        return Some("under a minute".to_string());
+    }
+
+    let days = total_minutes / (24 * 60);
+    let hours = (total_minutes % (24 * 60)) / 60;
+    let minutes = total_minutes % 60;
+
+    Some(if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    })
+}
+
+/// The limit windows present in `limits` as `(key, label, entry)`.
+pub fn limit_windows(
+    limits: &UsageLimits,
+) -> impl Iterator<Item = (&'static str, &'static str, &LimitEntry)> {
+    [
+        ("five_hour", &limits.five_hour),
+        ("seven_day", &limits.seven_day),
+        ("seven_day_opus", &limits.seven_day_opus),
+        ("seven_day_sonnet", &limits.seven_day_sonnet),
+        ("seven_day_cowork", &limits.seven_day_cowork),
+        ("seven_day_oauth_apps", &limits.seven_day_oauth_apps),
+    ]
+    .into_iter()
+    .filter_map(|(key, entry)| Some((key, limit_label(key), entry.as_ref()?)))
+}
+
+/// Display name for a limit window key such as `five_hour`.
+pub fn limit_label(window: &str) -> &'static str {
+    match window {
+        "five_hour" => "Session",
+        "seven_day" => "Weekly",
+        "seven_day_opus" => "Weekly Opus",
+        "seven_day_sonnet" => "Weekly Sonnet",
+        "seven_day_cowork" => "Weekly Cowork",
+        "seven_day_oauth_apps" => "Weekly OAuth apps",
+        "extra_usage" => "Extra usage",
+        _ => "Other",
+    }
+}
+
+fn most_constrained_utilization(limits: &UsageLimits) -> Option<f64> {
+    [
+        &limits.five_hour,
+        &limits.seven_day,
+        &limits.seven_day_sonnet,
+        &limits.seven_day_opus,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|entry| entry.utilization)
+    .reduce(f64::max)
+}
+
+/// Draws the base tray icon with a progress ring around it. The ring starts at
+/// twelve o'clock and fills clockwise; on macOS the result stays monochrome so
+/// it can be used as a template image.
+fn render_usage_icon(utilization: f64) -> Image<'static> {
+    const INNER_RADIUS: f64 = 10.5;
+    const RING_INNER: f64 = 12.5;
+    const RING_OUTER: f64 = 15.75;
+
+    let base = base_icon();
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+
+    let fraction = (utilization / 100.0).clamp(0.0, 1.0);
+    let scale = width.min(height) as f64 / 32.0;
+    let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+
+    #[cfg(target_os = "macos")]
+    let (fill, track) = ([0u8, 0, 0, 255], [0u8, 0, 0, 80]);
+    #[cfg(not(target_os = "macos"))]
+    let (fill, track) = (usage_color(utilization), [128u8, 128, 160, 110]);
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f64 + 0.5 - cx;
+            let dy = y as f64 + 0.5 - cy;
+            let dist = (dx * dx + dy * dy).sqrt() / scale;
+            let idx = ((y * width + x) * 4) as usize;
+
+            if dist <= INNER_RADIUS {
+                // Keep the original glyph, fading its edge so it sits inside the ring
+                let alpha = rgba[idx + 3] as f64 * coverage(INNER_RADIUS - dist);
+                rgba[idx + 3] = alpha.round() as u8;
+                continue;
+            }
+
+            let ring_coverage = coverage(dist - RING_INNER).min(coverage(RING_OUTER - dist));
+            if ring_coverage <= 0.0 {
+                rgba[idx..idx + 4].copy_from_slice(&[0, 0, 0, 0]);
+                continue;
+            }
+
+            // Angle measured clockwise from twelve o'clock, normalised to 0..1
+            let angle = (dx.atan2(-dy) / std::f64::consts::TAU).rem_euclid(1.0);
+            let color = if angle <= fraction { fill } else { track };
+            let alpha = color[3] as f64 * ring_coverage;
+            rgba[idx..idx + 4].copy_from_slice(&[
+                color[0],
+                color[1],
+                color[2],
+                alpha.round() as u8,
+            ]);
+        }
+    }
+
+    Image::new_owned(rgba, width, height)
+}
+
+/// Approximates pixel coverage for a signed distance to an edge (antialiasing).
+fn coverage(distance: f64) -> f64 {
+    (distance + 0.5).clamp(0.0, 1.0)
+}
+
+/// Matches the popup's limit bar colors (normal / high / critical).
+#[cfg(not(target_os = "macos"))]
+fn usage_color(utilization: f64) -> [u8; 4] {
+    if utilization >= 90.0 {
+        [0xef, 0x44, 0x44, 0xff]
+    } else if utilization >= 70.0 {
+        [0xf5, 0x9e, 0x0b, 0xff]
+    } else {
+        [0x3b, 0x82, 0xf6, 0xff]
+    }
+}
+
 fn should_toggle_on_click(event: &tauri::tray::TrayIconEvent) -> bool {
     match event {
         tauri::tray::TrayIconEvent::Click {
@@ -84,37 +621,120 @@ fn toggle_popup(app: &AppHandle) {
 }
 
 fn show_popup(window: &WebviewWindow) {
+    position_popup(window);
+
+    let _ = window.show();
+    let _ = window.set_focus();
+
+    position_popup(window);
+}
+
+/// Places the popup next to the tray icon. AppIndicator trays (GNOME, KDE)
+/// never report icon coordinates, so there we anchor to the cursor, which is
+/// next to the panel when the popup is opened from the tray menu.
+fn position_popup(window: &WebviewWindow) {
     if window
         .move_window_constrained(Position::TrayCenter)
         .is_err()
+        && position_near_cursor(window).is_none()
     {
-        let _ = window.move_window(Position::BottomRight);
+        let _ = window.move_window(fallback_corner(window));
     }
 
-    let _ = window.show();
-    let _ = window.set_focus();
+    snap_to_taskbar(window);
+}
 
+/// Re-anchors the popup after its content resized it. Without tray coordinates
+/// the popup stays where it was opened rather than chasing the cursor, and is
+/// only nudged back inside the work area if it grew past it.
+pub fn reposition_popup(window: &WebviewWindow) {
     if window
         .move_window_constrained(Position::TrayCenter)
         .is_err()
     {
-        let _ = window.move_window(Position::BottomRight);
+        clamp_to_work_area(window);
     }
 
-    // Snap popup flush against the taskbar using the work area bounds
-    #[cfg(target_os = "windows")]
-    {
-        if let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size()) {
-            if let Some(work_bottom) = get_work_area_bottom(pos.x, pos.y) {
-                let y = work_bottom - size.height as i32;
-                let _ = window.set_position(tauri::PhysicalPosition::new(pos.x, y));
-            }
+    snap_to_taskbar(window);
+}
+
+fn clamp_to_work_area(window: &WebviewWindow) -> Option<()> {
+    let monitor = window.current_monitor().ok()??;
+    let pos = window.outer_position().ok()?;
+    let size = window.outer_size().ok()?;
+    let area = monitor.work_area();
+
+    let max_x = area.position.x + area.size.width as i32 - size.width as i32;
+    let max_y = area.position.y + area.size.height as i32 - size.height as i32;
+    let x = pos.x.clamp(area.position.x, max_x.max(area.position.x));
+    let y = pos.y.clamp(area.position.y, max_y.max(area.position.y));
+
+    if (x, y) != (pos.x, pos.y) {
+        let _ = window.set_position(tauri::PhysicalPosition::new(x, y));
+    }
+    Some(())
+}
+
+/// Snaps the popup flush against the taskbar using the work area bounds.
+#[cfg(target_os = "windows")]
+fn snap_to_taskbar(window: &WebviewWindow) {
+    if let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size()) {
+        if let Some(work_bottom) = get_work_area_bottom(pos.x, pos.y) {
+            let y = work_bottom - size.height as i32;
+            let _ = window.set_position(tauri::PhysicalPosition::new(pos.x, y));
         }
     }
 }
 
+#[cfg(not(target_os = "windows"))]
+fn snap_to_taskbar(_window: &WebviewWindow) {}
+
+fn position_near_cursor(window: &WebviewWindow) -> Option<()> {
+    const CURSOR_GAP: i32 = 8;
+
+    let cursor = window.cursor_position().ok()?;
+    let monitor = window.monitor_from_point(cursor.x, cursor.y).ok()??;
+    let size = window.outer_size().ok()?;
+    let area = monitor.work_area();
+
+    let (width, height) = (size.width as i32, size.height as i32);
+    let left = area.position.x;
+    let top = area.position.y;
+    let right = left + area.size.width as i32;
+    let bottom = top + area.size.height as i32;
+    let (cursor_x, cursor_y) = (cursor.x as i32, cursor.y as i32);
+
+    // Open away from whichever screen edge the panel is on
+    let x = (cursor_x - width / 2).clamp(left, (right - width).max(left));
+    let y = if cursor_y < top + (bottom - top) / 2 {
+        cursor_y + CURSOR_GAP
+    } else {
+        cursor_y - height - CURSOR_GAP
+    };
+    let y = y.clamp(top, (bottom - height).max(top));
+
+    window.set_position(tauri::PhysicalPosition::new(x, y)).ok()
+}
+
+/// Picks the corner next to the panel when neither tray nor cursor positions
+/// are available (e.g. Wayland). A work area that starts below the monitor's
+/// top edge means the panel is at the top, as on GNOME.
+fn fallback_corner(window: &WebviewWindow) -> Position {
+    let panel_on_top = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .is_some_and(|monitor| monitor.work_area().position.y > monitor.position().y);
+
+    if panel_on_top {
+        Position::TopRight
+    } else {
+        Position::BottomRight
+    }
+}
+
 #[cfg(target_os = "windows")]
-pub fn get_work_area_bottom(x: i32, y: i32) -> Option<i32> {
+fn get_work_area_bottom(x: i32, y: i32) -> Option<i32> {
     #[repr(C)]
     struct RECT {
         left: i32,
@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::commands::{is_subagent_transcript, session_paths, transcript_owner};
+use crate::models::{TimeRange, UsageShare};
+use crate::pricing;
+
+const DEFAULT_RANGE: TimeDelta = TimeDelta::days(30);
+
+/// One assistant reply with its token usage, deduplicated across transcripts
+/// (resumed sessions repeat earlier messages under the same id).
+#[derive(Debug, Clone, Default)]
+pub struct UsageRecord {
+    pub session_id: String,
+    /// Working directory the session ran in
+    pub project: String,
+    pub timestamp: DateTime<Local>,
+    /// Empty for replies that never recorded one (e.g. cut off before the
+    /// first content block); they still count as messages
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    /// Split of the cache writes by ephemeral cache lifetime, when the
+    /// transcript records it
+    pub cache_creation_5m_tokens: u64,
+    pub cache_creation_1h_tokens: u64,
+    pub web_search_requests: u64,
+    /// Distinct tool_use blocks in the reply, in order
+    pub tool_uses: Vec<ToolUse>,
+    /// Written by a subagent (Task tool) rather than the main conversation;
+    /// `session_id` is still the parent session's
+    pub sidechain: bool,
+    pub agent_id: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolUse {
+    pub id: String,
+    /// e.g. `Bash`, or `mcp__<server>__<tool>` for MCP tools
+    pub name: String,
+}
+
+/// A user line: a prompt or the tool results sent back to the model.
+#[derive(Debug, Clone)]
+pub struct UserMessage {
+    pub session_id: String,
+    pub timestamp: DateTime<Local>,
+    /// Sent to a subagent rather than the main conversation
+    pub sidechain: bool,
+}
+
+/// Assistant replies and user messages read from the transcripts.
+#[derive(Debug, Default)]
+pub struct UsageScan {
+    pub records: Vec<UsageRecord>,
+    /// Deduplicated by uuid
+    pub user_messages: Vec<UserMessage>,
+    /// Results by tool use id
+    pub tool_results: HashMap<String, ToolResult>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolResult {
+    pub is_error: bool,
+    /// Bytes of text (or serialized JSON for other blocks) sent back to the
+    /// model
+    pub size: u64,
+}
+
+impl UsageRecord {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.cache_read_input_tokens
+            + self.cache_creation_input_tokens
+    }
+
+    pub fn cost_usd(&self) -> f64 {
+        pricing::estimate_cost(
+            &self.model,
+            self.input_tokens,
+            self.output_tokens,
+            self.cache_read_input_tokens,
+            self.cache_creation_input_tokens,
+            self.cache_creation_1h_tokens,
+        )
+    }
+}
+
+impl UsageScan {
+    /// Session and time of every message, user lines and assistant replies
+    /// alike; this is what the stats cache counts as messages.
+    pub fn messages(&self) -> impl Iterator<Item = (&str, DateTime<Local>)> {
+        self.user_messages
+            .iter()
+            .map(|m| (m.session_id.as_str(), m.timestamp))
+            .chain(
+                self.records
+                    .iter()
+                    .map(|r| (r.session_id.as_str(), r.timestamp)),
+            )
+    }
+}
+
+impl UsageShare {
+    pub fn new(name: &str) -> Self {
+        UsageShare {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn add(&mut self, record: &UsageRecord) {
+        if !record.model.is_empty() && !self.ids.contains(&record.model) {
+            self.ids.push(record.model.clone());
+            if let Some(snapshot) = crate::model_mix::normalize(&record.model).snapshot {
+                if !self.snapshots.contains(&snapshot) {
+                    self.snapshots.push(snapshot);
+                }
+            }
+        }
+        self.replies += 1;
+        self.total_tokens += record.total_tokens();
+        self.cost_usd += record.cost_usd();
+    }
+}
+
+/// Fills in each row's share of `total_tokens` and orders them by tokens,
+/// most first.
+pub fn by_tokens(rows: HashMap<String, UsageShare>, total_tokens: u64) -> Vec<UsageShare> {
+    let mut rows: Vec<UsageShare> = rows
+        .into_values()
+        .map(|mut row| {
+            row.share = percent(row.total_tokens, total_tokens);
+            row.ids.sort();
+            row.snapshots.sort();
+            row
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.total_tokens
+            .cmp(&a.total_tokens)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    rows
+}
+
+/// `part` as a percentage of `total`, or 0 when the total is.
+pub fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
+    }
+}
+
+/// Reads every session transcript and returns its assistant replies, oldest
+/// first.
+pub fn scan_usage() -> Vec<UsageRecord> {
+    scan_usage_since(None)
+}
+
+/// Like [`scan_usage`], but only returns replies from `since` onwards and
+/// skips transcripts that haven't been written to since then.
+pub fn scan_usage_since(since: Option<DateTime<Local>>) -> Vec<UsageRecord> {
+    scan(since).records
+}
+
+/// Reads the transcripts written to since `since` (all of them for `None`)
+/// and returns the assistant replies and user messages from then on, oldest
+/// first.
+pub fn scan(since: Option<DateTime<Local>>) -> UsageScan {
+    let paths = session_paths().into_iter().filter(|path| {
+        let modified = fs::metadata(path).and_then(|m| m.modified());
+        since.is_none_or(|since| {
+            !modified.is_ok_and(|modified| DateTime::<Local>::from(modified) < since)
+        })
+    });
+    read(paths, since)
+}
+
+/// Like [`scan`], but also leaves out anything from `to` onwards.
+pub fn scan_between(from: DateTime<Local>, to: DateTime<Local>) -> UsageScan {
+    let mut scan = scan(Some(from));
+    scan.records.retain(|r| r.timestamp < to);
+    scan.user_messages.retain(|m| m.timestamp < to);
+    scan
+}
+
+/// Scans a report's range and returns it with the resolved bounds.
+pub fn scan_range(
+    range: &TimeRange,
+) -> Result<(DateTime<Local>, DateTime<Local>, UsageScan), String> {
+    let (from, to) = report_range(range)?;
+    Ok((from, to, scan_between(from, to)))
+}
+
+/// Bounds of a report's range, the last 30 days unless the request gives a
+/// start.
+pub fn report_range(range: &TimeRange) -> Result<(DateTime<Local>, DateTime<Local>), String> {
+    crate::timeseries::resolve_range(range, DEFAULT_RANGE)
+}
+
+/// Like [`scan`], but reads just the given transcripts, in full.
+pub fn scan_files(paths: &[PathBuf]) -> UsageScan {
+    read(paths.iter().cloned(), None)
+}
+
+// Unreadable lines are skipped instead of ending the transcript
+#[allow(clippy::lines_filter_map_ok)]
+fn read(paths: impl Iterator<Item = PathBuf>, since: Option<DateTime<Local>>) -> UsageScan {
+    let mut records: HashMap<String, UsageRecord> = HashMap::new();
+    // Streamed replies repeat their content blocks across lines
+    let mut tool_uses: HashMap<String, Vec<ToolUse>> = HashMap::new();
+    let mut user_messages: HashMap<String, UserMessage> = HashMap::new();
+    let mut tool_results: HashMap<String, ToolResult> = HashMap::new();
+
+    for path in paths {
+        // Fallbacks when lines carry no sessionId or cwd; the project is the
+        // encoded project directory name
+        let (project_from_path, session_id_from_path) = transcript_owner(&path);
+        let subagent_file = is_subagent_transcript(&path);
+
+        let Ok(file) = fs::File::open(&path) else {
+            continue;
+        };
+        let reader = BufReader::new(file);
+
+        for (idx, line) in reader.lines().flatten().enumerate() {
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            let kind = value.get("type").and_then(|t| t.as_str());
+            if kind != Some("assistant") && kind != Some("user") {
+                continue;
+            }
+            let Some(timestamp) = value
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.with_timezone(&Local))
+            else {
+                continue;
+            };
+
+            let session_id = value
+                .get("sessionId")
+                .and_then(|s| s.as_str())
+                .unwrap_or(&session_id_from_path);
+            let project = value
+                .get("cwd")
+                .and_then(|c| c.as_str())
+                .unwrap_or(&project_from_path);
+            let message = value.get("message").unwrap_or(&serde_json::Value::Null);
+            let content = message
+                .get("content")
+                .and_then(|c| c.as_array())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let sidechain =
+                subagent_file || value.get("isSidechain").and_then(|s| s.as_bool()) == Some(true);
+            let blocks = |kind: &'static str| {
+                content
+                    .iter()
+                    .filter(move |b| b.get("type").and_then(|t| t.as_str()) == Some(kind))
+            };
+
+            if kind == Some("user") {
+                let uuid = value
+                    .get("uuid")
+                    .and_then(|u| u.as_str())
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| format!("{session_id}:{idx}:user"));
+                user_messages.entry(uuid).or_insert_with(|| UserMessage {
+                    session_id: session_id.to_string(),
+                    timestamp,
+                    sidechain,
+                });
+                for result in blocks("tool_result") {
+                    let Some(id) = result.get("tool_use_id").and_then(|id| id.as_str()) else {
+                        continue;
+                    };
+                    tool_results.insert(
+                        id.to_string(),
+                        ToolResult {
+                            is_error: result.get("is_error").and_then(|e| e.as_bool())
+                                == Some(true),
+                            size: result.get("content").map_or(0, content_size),
+                        },
+                    );
+                }
+                continue;
+            }
+
+            let usage = message.get("usage").unwrap_or(&serde_json::Value::Null);
+            let message_id = message
+                .get("id")
+                .or_else(|| value.get("uuid"))
+                .and_then(|id| id.as_str())
+                .map(ToString::to_string)
+                .unwrap_or_else(|| format!("{session_id}:{idx}:assistant"));
+
+            add_tool_uses(tool_uses.entry(message_id.clone()).or_default(), content);
+
+            let record = records.entry(message_id).or_insert_with(|| UsageRecord {
+                session_id: session_id.to_string(),
+                project: project.to_string(),
+                timestamp,
+                sidechain,
+                agent_id: value
+                    .get("agentId")
+                    .and_then(|a| a.as_str())
+                    .map(ToString::to_string),
+                ..Default::default()
+            });
+
+            if let Some(model) = message.get("model").and_then(|m| m.as_str()) {
+                if !model.is_empty() {
+                    record.model = model.to_string();
+                }
+            }
+            let tokens = |key: &str| usage.get(key).and_then(|t| t.as_u64()).unwrap_or(0);
+            record.input_tokens = record.input_tokens.max(tokens("input_tokens"));
+            record.output_tokens = record.output_tokens.max(tokens("output_tokens"));
+            record.cache_read_input_tokens = record
+                .cache_read_input_tokens
+                .max(tokens("cache_read_input_tokens"));
+            record.cache_creation_input_tokens = record
+                .cache_creation_input_tokens
+                .max(tokens("cache_creation_input_tokens"));
+            let server_tool_use = usage.get("server_tool_use");
+            let web_search_requests = tokens("web_search_requests").max(
+                server_tool_use
+                    .and_then(|s| s.get("web_search_requests"))
+                    .and_then(|t| t.as_u64())
+                    .unwrap_or(0),
+            );
+            record.web_search_requests = record.web_search_requests.max(web_search_requests);
+            let cache_creation = usage.get("cache_creation");
+            let ephemeral = |key: &str| {
+                cache_creation
+                    .and_then(|c| c.get(key))
+                    .and_then(|t| t.as_u64())
+                    .unwrap_or(0)
+            };
+            record.cache_creation_5m_tokens = record
+                .cache_creation_5m_tokens
+                .max(ephemeral("ephemeral_5m_input_tokens"));
+            record.cache_creation_1h_tokens = record
+                .cache_creation_1h_tokens
+                .max(ephemeral("ephemeral_1h_input_tokens"));
+        }
+    }
+
+    let mut records: Vec<UsageRecord> = records
+        .into_iter()
+        .filter(|(_, record)| since.is_none_or(|since| record.timestamp >= since))
+        .map(|(id, mut record)| {
+            record.tool_uses = tool_uses.remove(&id).unwrap_or_default();
+            record
+        })
+        .collect();
+    records.sort_by_key(|record| record.timestamp);
+
+    let mut user_messages: Vec<UserMessage> = user_messages
+        .into_values()
+        .filter(|m| since.is_none_or(|since| m.timestamp >= since))
+        .collect();
+    user_messages.sort_by_key(|m| m.timestamp);
+
+    UsageScan {
+        records,
+        user_messages,
+        tool_results,
+    }
+}
+
+/// Adds the tool_use blocks of one assistant line to `uses`, skipping blocks an
+/// earlier line of the same streamed reply already recorded. Blocks without an
+/// id are keyed by their JSON.
+pub(crate) fn add_tool_uses(uses: &mut Vec<ToolUse>, content: &[serde_json::Value]) {
+    for block in content {
+        if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+            continue;
+        }
+        let id = block
+            .get("id")
+            .and_then(|id| id.as_str())
+            .map(ToString::to_string)
+            .unwrap_or_else(|| format!("anon:{block}"));
+        if uses.iter().any(|u| u.id == id) {
+            continue;
+        }
+        uses.push(ToolUse {
+            id,
+            name: block
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        });
+    }
+}
+
+fn content_size(content: &serde_json::Value) -> u64 {
+    match content {
+        serde_json::Value::String(text) => text.len() as u64,
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .map(|block| match block.get("text").and_then(|t| t.as_str()) {
+                Some(text) => text.len() as u64,
+                None => block.to_string().len() as u64,
+            })
+            .sum(),
+        serde_json::Value::Null => 0,
+        other => other.to_string().len() as u64,
+    }
+}
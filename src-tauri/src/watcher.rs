@@ -30,10 +30,12 @@ pub fn start_watcher(app: AppHandle) {
                         match filename {
                             "stats-cache.json" => {
                                 let _ = app.emit("stats-updated", ());
+                                crate::tray::schedule_menu_refresh(&app);
                             }
                             "history.jsonl" => {
                                 let _ = app.emit("stats-updated", ());
                                 let _ = app.emit("history-updated", ());
+                                crate::tray::schedule_menu_refresh(&app);
                             }
                             _ => {
                                 let is_project_jsonl = path
@@ -46,6 +48,8 @@ pub fn start_watcher(app: AppHandle) {
 
                                 if is_project_jsonl {
                                     let _ = app.emit("stats-updated", ());
+                                    crate::tray::schedule_menu_refresh(&app);
+                                    crate::budgets::schedule_refresh(&app);
                                 }
                             }
                         }
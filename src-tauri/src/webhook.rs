@@ -0,0 +1,249 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::{Captures, Regex};
+use serde_json::Value;
+
+use crate::models::{Alert, WebhookConfig, WebhookFormat};
+
+const MAX_ATTEMPTS: u32 = 3;
+#[cfg(not(test))]
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+/// Keeps the retry tests from sitting through the real backoff
+#[cfg(test)]
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `{{title}}`, `{{ summary.totalTokens }}`, ...
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z0-9_.]+)\s*\}\}").unwrap());
+
+/// Sends `alert` to every enabled webhook in the background, logging failures.
+pub fn dispatch(webhooks: &[WebhookConfig], alert: &Alert) {
+    for webhook in webhooks.iter().filter(|w| w.enabled && !w.url.is_empty()) {
+        let webhook = webhook.clone();
+        let alert = alert.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = send(&webhook, &alert).await {
+                eprintln!("webhook error ({}): {e}", webhook.url);
+            }
+        });
+    }
+}
+
+/// Posts the alert, retrying network errors, 429s and 5xx responses with
+/// exponential backoff.
+pub async fn send(webhook: &WebhookConfig, alert: &Alert) -> Result<(), String> {
+    let body = render_body(webhook, alert)?;
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+
+    let mut attempt = 1;
+    loop {
+        let (retryable, error) = match client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .body(body.clone())
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => return Ok(()),
+            Ok(resp) => {
+                let status = resp.status();
+                (
+                    status.is_server_error() || status.as_u16() == 429,
+                    format!("Webhook returned {status}"),
+                )
+            }
+            Err(e) => (true, format!("Webhook request failed: {e}")),
+        };
+
+        if !retryable || attempt >= MAX_ATTEMPTS {
+            return Err(error);
+        }
+        tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+        attempt += 1;
+    }
+}
+
+/// Builds the request body: the alert itself for JSON webhooks, a `text`
+/// message for Slack, or the user's template with placeholders filled in.
+pub fn render_body(webhook: &WebhookConfig, alert: &Alert) -> Result<String, String> {
+    let fields =
+        serde_json::to_value(alert).map_err(|e| format!("Failed to serialize alert: {e}"))?;
+
+    if webhook.template.trim().is_empty() {
+        let body = match webhook.format {
+            WebhookFormat::Json => fields,
+            WebhookFormat::Slack => serde_json::json!({
+                "text": format!("*{}*\n{}", alert.title, alert.message),
+            }),
+        };
+        return Ok(body.to_string());
+    }
+
+    let rendered = PLACEHOLDER.replace_all(&webhook.template, |caps: &Captures| {
+        let pointer = format!("/{}", caps[1].replace('.', "/"));
+        match fields.pointer(&pointer) {
+            // Escaped without the surrounding quotes, so templates quote strings themselves
+            Some(Value::String(text)) => {
+                let quoted = Value::String(text.clone()).to_string();
+                quoted[1..quoted.len() - 1].to_string()
+            }
+            Some(Value::Null) | None => "null".to_string(),
+            Some(other) => other.to_string(),
+        }
+    });
+
+    serde_json::from_str::<Value>(&rendered)
+        .map_err(|e| format!("Webhook template is not valid JSON once filled in: {e}"))?;
+    Ok(rendered.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::JoinHandle;
+
+    use super::*;
+
+    /// Answers one request per status in `statuses`, then waits briefly for
+    /// any extra one. Yields the bodies received and whether an extra request
+    /// came in.
+    fn listener(statuses: Vec<u16>) -> (String, JoinHandle<(Vec<String>, bool)>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", server.server_addr().to_ip().unwrap());
+        let handle = std::thread::spawn(move || {
+            let bodies = statuses
+                .into_iter()
+                .map(|status| {
+                    let mut request = server.recv().unwrap();
+                    let content_type = request
+                        .headers()
+                        .iter()
+                        .find(|h| h.field.equiv("Content-Type"))
+                        .map(|h| h.value.to_string());
+                    assert_eq!(content_type.as_deref(), Some("application/json"));
+                    let mut body = String::new();
+                    request.as_reader().read_to_string(&mut body).unwrap();
+                    request.respond(tiny_http::Response::empty(status)).unwrap();
+                    body
+                })
+                .collect();
+            let extra = server
+                .recv_timeout(Duration::from_millis(500))
+                .unwrap()
+                .is_some();
+            (bodies, extra)
+        });
+        (url, handle)
+    }
+
+    fn webhook(url: &str, format: WebhookFormat, template: &str) -> WebhookConfig {
+        WebhookConfig {
+            url: url.to_string(),
+            format,
+            template: template.to_string(),
+            enabled: true,
+        }
+    }
+
+    fn send_blocking(webhook: &WebhookConfig) -> Result<(), String> {
+        tauri::async_runtime::block_on(send(webhook, &crate::alerts::test_alert()))
+    }
+
+    #[test]
+    fn json_body_is_the_alert() {
+        let alert = crate::alerts::test_alert();
+        let body = render_body(&webhook("", WebhookFormat::Json, ""), &alert).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap(),
+            serde_json::to_value(&alert).unwrap()
+        );
+    }
+
+    #[test]
+    fn slack_body_is_a_text_message() {
+        let alert = crate::alerts::test_alert();
+        let body = render_body(&webhook("", WebhookFormat::Slack, ""), &alert).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap(),
+            serde_json::json!({
+                "text": "*Brew Status test alert*\nWebhook alerts from Brew Status are working."
+            })
+        );
+    }
+
+    #[test]
+    fn template_fills_in_placeholders() {
+        let mut alert = crate::alerts::test_alert();
+        alert.title = "Say \"hi\"".to_string();
+        let template = r#"{"title": "{{ title }}", "kind": "{{kind}}", "used": {{utilization}}}"#;
+        let body = render_body(&webhook("", WebhookFormat::Json, template), &alert).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap(),
+            serde_json::json!({ "title": "Say \"hi\"", "kind": "test", "used": 42.0 })
+        );
+    }
+
+    #[test]
+    fn template_renders_null_and_missing_fields_as_null() {
+        let alert = crate::alerts::test_alert();
+        let template = r#"{"resets": {{resetsAt}}, "tokens": {{summary.totalTokens}}}"#;
+        let body = render_body(&webhook("", WebhookFormat::Json, template), &alert).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap(),
+            serde_json::json!({ "resets": null, "tokens": null })
+        );
+    }
+
+    #[test]
+    fn template_must_be_valid_json_once_filled_in() {
+        let alert = crate::alerts::test_alert();
+        let template = r#"{"title": {{title}}}"#;
+        assert!(render_body(&webhook("", WebhookFormat::Json, template), &alert).is_err());
+    }
+
+    #[test]
+    fn send_posts_the_rendered_body() {
+        let (url, server) = listener(vec![200]);
+        let webhook = webhook(&url, WebhookFormat::Slack, "");
+        send_blocking(&webhook).unwrap();
+
+        let (bodies, extra) = server.join().unwrap();
+        let expected = render_body(&webhook, &crate::alerts::test_alert()).unwrap();
+        assert_eq!(bodies, vec![expected]);
+        assert!(!extra);
+    }
+
+    #[test]
+    fn send_retries_server_errors_and_rate_limits() {
+        let (url, server) = listener(vec![500, 429, 200]);
+        send_blocking(&webhook(&url, WebhookFormat::Json, "")).unwrap();
+
+        let (bodies, extra) = server.join().unwrap();
+        assert_eq!(bodies.len(), 3);
+        assert!(!extra);
+    }
+
+    #[test]
+    fn send_gives_up_after_max_attempts() {
+        let (url, server) = listener(vec![503; MAX_ATTEMPTS as usize]);
+        let error = send_blocking(&webhook(&url, WebhookFormat::Json, "")).unwrap_err();
+        assert!(error.contains("503"), "{error}");
+
+        let (_, extra) = server.join().unwrap();
+        assert!(!extra);
+    }
+
+    #[test]
+    fn send_does_not_retry_client_errors() {
+        let (url, server) = listener(vec![400]);
+        let error = send_blocking(&webhook(&url, WebhookFormat::Json, "")).unwrap_err();
+        assert!(error.contains("400"), "{error}");
+
+        let (_, extra) = server.join().unwrap();
+        assert!(!extra);
+    }
+}
@@ -1,15 +1,46 @@
 import {
   formatTokenCount,
   formatNumber,
+  formatBytes,
   formatDuration,
+  formatCost,
   formatDate,
   formatShortDate,
   timeAgo,
   modelDisplayName,
   formatResetTime,
 } from "./shared/formatters";
-import { renderBarChart, renderHourlyHeatmap } from "./shared/chart";
-import type { StatsCache, HistoryEntry, UsageLimits, LimitEntry, ProfileResponse, UpdateResult } from "./shared/types";
+import { renderBarChart, renderHourlyHeatmap, renderWeekHeatmap } from "./shared/chart";
+import type {
+  StatsCache,
+  HistoryEntry,
+  HistoryPage,
+  UsageLimits,
+  LimitEntry,
+  ProfileResponse,
+  UpdateResult,
+  UpdateProgress,
+  Settings,
+  SessionSummary,
+  ExportFormat,
+  ExportRequest,
+  WebhookConfig,
+  WebhookFormat,
+  DigestPeriod,
+  ExtraUsageHistory,
+  FiveHourWindow,
+  TimeseriesMetric,
+  ToolUsage,
+  McpUsage,
+  SubagentUsage,
+  CacheReport,
+  ModelMix,
+  WeeklyPattern,
+  UsageShare,
+  Budget,
+  BudgetPeriod,
+  BudgetUnit,
+} from "./shared/types";
 
 const { invoke } = window.__TAURI__.core;
 const { listen } = window.__TAURI__.event;
@@ -19,6 +50,12 @@ const isMacOS =
   navigator.platform.toLowerCase().includes("mac");
 
 let cachedStats: StatsCache | null = null;
+let currentSettings: Settings | null = null;
+let historySearchText = "";
+let historyCursor: string | null = null;
+let historyEntries: HistoryEntry[] = [];
+let historyLoading = false;
+let historySearchTimer: number | null = null;
 let currentMetric: "tokens" | "messages" | "toolCalls" = "tokens";
 
 if (isMacOS) {
@@ -64,12 +101,27 @@ async function loadStats(): Promise<void> {
   }
 }
 
-async function loadHistory(): Promise<void> {
+async function loadHistory(append = false): Promise<void> {
+  if (append && (!historyCursor || historyLoading)) {
+    return;
+  }
+
+  historyLoading = true;
   try {
-    const entries = await invoke<HistoryEntry[]>("get_history", { limit: 50 });
-    renderHistory(entries);
+    const cursor = append ? historyCursor : null;
+    const page = historySearchText
+      ? await invoke<HistoryPage>("search_history", {
+          query: { text: historySearchText, limit: 50, cursor },
+        })
+      : await invoke<HistoryPage>("get_history_page", { cursor, limit: 50 });
+    historyEntries = append ? historyEntries.concat(page.entries) : page.entries;
+    historyCursor = page.nextCursor;
+    renderHistory(historyEntries);
+    document.getElementById("history-more")!.classList.toggle("hidden", !page.nextCursor);
   } catch (e) {
     console.error("Failed to load history:", e);
+  } finally {
+    historyLoading = false;
   }
 }
 
@@ -150,6 +202,19 @@ function renderHeatmap(stats: StatsCache): void {
   renderHourlyHeatmap(canvas, stats.hourCounts);
 }
 
+async function loadWeeklyPattern(): Promise<void> {
+  const select = document.getElementById("pattern-metric") as HTMLSelectElement;
+  try {
+    const pattern = await invoke<WeeklyPattern>("get_weekly_pattern", {
+      metric: select.value as TimeseriesMetric,
+    });
+    const canvas = document.getElementById("pattern-canvas") as HTMLCanvasElement;
+    renderWeekHeatmap(canvas, pattern.cells);
+  } catch (e) {
+    console.error("Failed to load weekly pattern:", e);
+  }
+}
+
 function renderActivityChart(
   stats: StatsCache,
   metric: "tokens" | "messages" | "toolCalls"
@@ -184,22 +249,48 @@ function renderHistory(entries: HistoryEntry[]): void {
         ? entry.project.split("\\").pop() || entry.project.split("/").pop() || ""
         : "";
       const message = escapeHtml(entry.display).slice(0, 120);
+      const linked = entry.sessionId ? ` data-session="${escapeHtml(entry.sessionId)}" title="Show transcript"` : "";
       return `
-        <div class="history-item">
+        <div class="history-item${entry.sessionId ? " linked" : ""}"${linked}>
           <span class="history-time">${timeAgo(entry.timestamp)}</span>
           <span class="history-message">${message}</span>
           <span class="history-project">${escapeHtml(project)}</span>
+          ${renderSessionMeta(entry.session)}
         </div>`;
     })
     .join("");
 }
 
+function renderSessionMeta(session: SessionSummary | null | undefined): string {
+  if (!session) return "";
+
+  const tokens =
+    session.inputTokens +
+    session.outputTokens +
+    session.cacheReadInputTokens +
+    session.cacheCreationInputTokens;
+  const models = [...new Set(session.models.map(modelDisplayName))].join(", ");
+  const parts = [
+    models,
+    `${formatTokenCount(tokens)} tokens`,
+    session.subagentTokens ? `${formatTokenCount(session.subagentTokens)} in subagents` : "",
+    formatCost(session.costUsd),
+    formatDuration(session.durationMs),
+  ].filter(Boolean);
+
+  return `<span class="history-session">${escapeHtml(parts.join(" · "))}</span>`;
+}
+
 function escapeHtml(text: string): string {
   const el = document.createElement("span");
   el.textContent = text;
   return el.innerHTML;
 }
 
+function escapeAttr(text: string): string {
+  return escapeHtml(text).replace(/"/g, "&quot;");
+}
+
 function renderDashboardLimitBar(label: string, entry: LimitEntry): string {
   const pct = Math.round(entry.utilization);
   const fillClass = pct >= 90 ? "critical" : pct >= 70 ? "high" : "";
@@ -261,12 +352,201 @@ async function loadLimits(): Promise<void> {
     }
 
     container.innerHTML = html;
+    loadFiveHourWindow();
   } catch (e) {
     container.innerHTML = '<div class="limits-error">Failed to load limits. Check your OAuth credentials.</div>';
     console.error("Failed to load limits:", e);
   }
 }
 
+function renderWindowRows(title: string, rows: UsageShare[]): string {
+  const items = rows
+    .slice(0, 5)
+    .map(
+      (row) => `
+      <div class="breakdown-row">
+        <span class="breakdown-row-name" title="${escapeAttr(row.name)}">${escapeHtml(row.name)}</span>
+        <span class="breakdown-row-value">${formatTokenCount(row.totalTokens)} &middot; ${Math.round(row.share)}%</span>
+      </div>`,
+    )
+    .join("");
+  return `<div><h3>${title}</h3>${items}</div>`;
+}
+
+async function loadFiveHourWindow(): Promise<void> {
+  const container = document.getElementById("window-breakdown")!;
+  try {
+    const usage = await invoke<FiveHourWindow>("get_five_hour_window");
+    container.classList.toggle("hidden", usage.totalTokens === 0);
+    if (usage.totalTokens === 0) return;
+
+    const since = new Date(usage.start).toLocaleTimeString("en-US", { hour: "numeric", minute: "2-digit" });
+    let summary = `Since ${since}: ${formatTokenCount(usage.totalTokens)} tokens, ${formatNumber(usage.messages)} messages, ~${formatCost(usage.costUsd)}`;
+    if (usage.tokensPerPercent !== null && usage.remainingTokens !== null) {
+      summary += ` &middot; ~${formatTokenCount(Math.round(usage.tokensPerPercent))} tokens per 1%, ~${formatTokenCount(usage.remainingTokens)} left`;
+    }
+    container.innerHTML = `
+      <div class="window-summary">${summary}</div>
+      <div class="window-columns">
+        ${renderWindowRows("By model", usage.models)}
+        ${renderWindowRows("By project", usage.projects)}
+      </div>`;
+  } catch (e) {
+    container.classList.add("hidden");
+    console.error("Failed to load five-hour window:", e);
+  }
+}
+
+async function loadToolUsage(): Promise<void> {
+  const list = document.getElementById("tool-list")!;
+  try {
+    const usage = await invoke<ToolUsage>("get_tool_usage");
+    document.getElementById("tool-summary")!.textContent = usage.calls
+      ? `${formatNumber(usage.calls)} calls, ${usage.errorRate.toFixed(1)}% errors`
+      : "";
+    if (usage.tools.length === 0) {
+      list.innerHTML = '<div class="limits-loading">No tool calls yet</div>';
+      return;
+    }
+    list.innerHTML = usage.tools
+      .slice(0, 12)
+      .map((tool) => {
+        const name = tool.mcpServer ? `${tool.mcpServer} (MCP)` : tool.name;
+        const errors = tool.errors ? ` &middot; ${tool.errorRate.toFixed(1)}% errors` : "";
+        return `
+          <div class="breakdown-row">
+            <span class="breakdown-row-name" title="${escapeAttr(name)}">${escapeHtml(name)}</span>
+            <span class="breakdown-row-value">${formatNumber(tool.calls)}${errors}</span>
+          </div>`;
+      })
+      .join("");
+  } catch (e) {
+    console.error("Failed to load tool usage:", e);
+  }
+}
+
+async function loadMcpUsage(): Promise<void> {
+  try {
+    const usage = await invoke<McpUsage>("get_mcp_usage");
+    document.getElementById("mcp-section")!.classList.toggle("hidden", usage.servers.length === 0);
+    document.getElementById("mcp-list")!.innerHTML = usage.servers
+      .map((server) => {
+        const tools = server.tools.map((tool) => `${tool.name}: ${formatNumber(tool.calls)}`).join(", ");
+        return `
+          <div class="breakdown-row" title="${escapeAttr(tools)}">
+            <span class="breakdown-row-name">${escapeHtml(server.server)}</span>
+            <span class="breakdown-row-value">${formatNumber(server.calls)} calls &middot; ${formatBytes(server.resultBytes)} &middot; ${formatTokenCount(server.turnTokens)} tokens</span>
+          </div>`;
+      })
+      .join("");
+  } catch (e) {
+    console.error("Failed to load MCP usage:", e);
+  }
+}
+
+async function loadSubagentUsage(): Promise<void> {
+  try {
+    const usage = await invoke<SubagentUsage>("get_subagent_usage");
+    document.getElementById("subagent-summary")!.textContent = usage.subagents.replies
+      ? `Subagents used ${usage.subagentShare.toFixed(1)}% of tokens in the last 30 days (${formatTokenCount(usage.subagents.totalTokens)}, ~${formatCost(usage.subagents.costUsd)}) across ${formatNumber(usage.sessions.length)} sessions`
+      : "";
+  } catch (e) {
+    console.error("Failed to load subagent usage:", e);
+  }
+}
+
+async function loadCacheReport(): Promise<void> {
+  try {
+    const report = await invoke<CacheReport>("get_cache_report");
+    const totals = report.totals;
+    const note = document.getElementById("cache-summary")!;
+    if (totals.cacheReadTokens + totals.cacheWriteTokens === 0) {
+      note.textContent = "";
+      return;
+    }
+
+    const savings =
+      totals.savingsUsd >= 0
+        ? `~${formatCost(totals.savingsUsd)} saved`
+        : `~${formatCost(-totals.savingsUsd)} more than uncached`;
+    const parts = [`Last 30 days: ${Math.round(totals.hitRatio)}% of prompt tokens read from cache, ${savings}`];
+    const classified = totals.cacheWrite5mTokens + totals.cacheWrite1hTokens;
+    if (classified > 0) {
+      const longShare = Math.round((totals.cacheWrite1hTokens / classified) * 100);
+      parts.push(`cache writes ${100 - longShare}% 5-minute / ${longShare}% 1-hour`);
+    }
+    if (report.rebuildingSessions.length > 0) {
+      const count = report.rebuildingSessions.length;
+      parts.push(`${count} session${count === 1 ? "" : "s"} kept rebuilding the cache`);
+    }
+    note.textContent = parts.join(" · ");
+  } catch (e) {
+    console.error("Failed to load cache report:", e);
+  }
+}
+
+async function loadModelMix(): Promise<void> {
+  try {
+    const mix = await invoke<ModelMix>("get_model_mix");
+    const note = document.getElementById("model-mix-summary")!;
+    if (mix.models.length === 0) {
+      note.textContent = "";
+      return;
+    }
+
+    const shares = mix.models
+      .slice(0, 3)
+      .map((m) => `${m.name} ${Math.round(m.share)}%`)
+      .join(", ");
+    const parts = [`Model mix: ${shares}`];
+    if (mix.switches.length > 0) {
+      const count = mix.switches.length;
+      parts.push(`${count} in-session model switch${count === 1 ? "" : "es"}`);
+    }
+    for (const limit of mix.limits) {
+      const family = limit.family === "opus" ? "Opus" : "Sonnet";
+      const rate = limit.tokensPerPercent ? ` (~${formatTokenCount(limit.tokensPerPercent)} per 1%)` : "";
+      parts.push(
+        `${family} this week: ${formatTokenCount(limit.totalTokens)} for ${Math.round(limit.utilization)}% of its limit${rate}`,
+      );
+    }
+    note.textContent = parts.join(" · ");
+  } catch (e) {
+    console.error("Failed to load model mix:", e);
+  }
+}
+
+async function loadExtraUsage(): Promise<void> {
+  try {
+    const history = await invoke<ExtraUsageHistory>("get_extra_usage_history", { days: 30 });
+    const panel = document.getElementById("extra-usage-panel")!;
+    panel.classList.toggle("hidden", history.daily.length === 0);
+    if (history.daily.length === 0) return;
+
+    const canvas = document.getElementById("extra-usage-chart") as HTMLCanvasElement;
+    renderBarChart(
+      canvas,
+      history.daily.map((d) => formatShortDate(d.date)),
+      history.daily.map((d) => d.spent),
+    );
+
+    const note = document.getElementById("extra-usage-projection")!;
+    const projection = history.projection;
+    if (!projection) {
+      note.textContent = "";
+      return;
+    }
+    const cycleEnd = new Date(projection.cycleEnd).toLocaleDateString("en-US", { month: "short", day: "numeric" });
+    const ofLimit =
+      projection.projectedUtilization !== null && projection.monthlyLimit !== null
+        ? ` (${Math.round(projection.projectedUtilization)}% of ${formatNumber(projection.monthlyLimit)})`
+        : "";
+    note.textContent = `${formatNumber(Math.round(projection.usedCredits))} used this cycle, on pace for ${formatNumber(Math.round(projection.projectedCredits))} by ${cycleEnd}${ofLimit}`;
+  } catch (e) {
+    console.error("Failed to load extra usage history:", e);
+  }
+}
+
 // Titlebar controls (not used on macOS when native traffic lights are shown)
 if (!isMacOS) {
   document.getElementById("btn-minimize")!.addEventListener("click", async () => {
@@ -280,6 +560,350 @@ if (!isMacOS) {
   });
 }
 
+// Settings panel
+type SettingInput = HTMLInputElement | HTMLSelectElement;
+
+function settingInputs(): NodeListOf<SettingInput> {
+  return document.querySelectorAll<SettingInput>("[data-setting]");
+}
+
+function renderSettings(settings: Settings): void {
+  settingInputs().forEach((input) => {
+    const value = settings[input.dataset.setting as keyof Settings];
+    if (input instanceof HTMLInputElement && input.type === "checkbox") {
+      input.checked = Boolean(value);
+    } else {
+      input.value = String(value ?? "");
+    }
+  });
+
+  const skipped = document.getElementById("about-skipped")!;
+  skipped.textContent = settings.skippedVersion ? `Skipping v${settings.skippedVersion}` : "";
+
+  renderUpdateSchedule(settings);
+  renderAlertSettings(settings);
+}
+
+function renderAlertSettings(settings: Settings): void {
+  (document.getElementById("alert-thresholds") as HTMLInputElement).value =
+    settings.alertThresholds.join(", ");
+  (document.getElementById("daily-summary-hour") as HTMLSelectElement).value =
+    settings.dailySummaryHour === null ? "" : String(settings.dailySummaryHour);
+
+  document.getElementById("webhook-list")!.innerHTML = settings.webhooks
+    .map(
+      (hook, index) => `
+        <div class="webhook-row" data-index="${index}">
+          <div class="webhook-fields">
+            <input type="checkbox" data-field="enabled" title="Enabled"${hook.enabled ? " checked" : ""} />
+            <input type="url" class="settings-select webhook-url" data-field="url" placeholder="https://hooks.slack.com/services/..." value="${escapeAttr(hook.url)}" />
+            <select class="settings-select" data-field="format">
+              <option value="json"${hook.format === "json" ? " selected" : ""}>JSON</option>
+              <option value="slack"${hook.format === "slack" ? " selected" : ""}>Slack</option>
+            </select>
+            <button class="about-btn" data-action="test">Test</button>
+            <button class="about-btn" data-action="remove">Remove</button>
+          </div>
+          <textarea class="settings-select webhook-template" data-field="template" rows="2" placeholder="Optional JSON body, e.g. {&quot;text&quot;: &quot;{{title}}: {{message}}&quot;}">${escapeHtml(hook.template)}</textarea>
+          <span class="about-note" data-role="status"></span>
+        </div>`,
+    )
+    .join("");
+}
+
+function renderBudgetSettings(settings: Settings): void {
+  const option = (value: string, label: string, selected: string) =>
+    `<option value="${value}"${value === selected ? " selected" : ""}>${label}</option>`;
+
+  document.getElementById("budget-list")!.innerHTML = settings.budgets
+    .map(
+      (budget, index) => `
+        <div class="budget-row" data-index="${index}">
+          <div class="budget-fields">
+            <input type="checkbox" data-field="enabled" title="Enabled"${budget.enabled ? " checked" : ""} />
+            <input type="text" class="settings-select budget-name" data-field="name" placeholder="Name" value="${escapeAttr(budget.name)}" />
+            <select class="settings-select" data-field="period">
+              ${option("daily", "Daily", budget.period)}
+              ${option("weekly", "Weekly", budget.period)}
+              ${option("monthly", "Monthly", budget.period)}
+            </select>
+            <input type="number" class="settings-select budget-limit" data-field="limit" min="0" step="any" value="${budget.limit}" />
+            <select class="settings-select" data-field="unit">
+              ${option("usd", "USD", budget.unit)}
+              ${option("tokens", "tokens", budget.unit)}
+            </select>
+            <button class="about-btn" data-action="remove">Remove</button>
+          </div>
+          <div class="budget-fields">
+            <input type="text" class="settings-select budget-filter" data-field="project" placeholder="Any project" value="${escapeAttr(budget.project ?? "")}" />
+            <input type="text" class="settings-select budget-filter" data-field="model" placeholder="Any model (e.g. opus)" value="${escapeAttr(budget.model ?? "")}" />
+            <input type="number" class="settings-select settings-port" data-field="warnPercent" min="1" max="100" title="Warn at %" value="${budget.warnPercent}" />
+            <label class="budget-block">
+              <input type="checkbox" data-field="block"${budget.block ? " checked" : ""} />
+              Block via hook
+            </label>
+          </div>
+        </div>`,
+    )
+    .join("");
+}
+
+function budgetFromRow(row: HTMLElement): Budget {
+  const field = <T extends HTMLInputElement | HTMLSelectElement>(name: string) =>
+    row.querySelector<T>(`[data-field="${name}"]`)!;
+  return {
+    name: field("name").value.trim(),
+    period: field("period").value as BudgetPeriod,
+    unit: field("unit").value as BudgetUnit,
+    limit: Number(field("limit").value) || 0,
+    project: field("project").value.trim() || null,
+    model: field("model").value.trim() || null,
+    warnPercent: Number(field("warnPercent").value) || 80,
+    block: field<HTMLInputElement>("block").checked,
+    enabled: field<HTMLInputElement>("enabled").checked,
+  };
+}
+
+function webhookFromRow(row: HTMLElement): WebhookConfig {
+  const field = <T extends HTMLElement>(name: string) =>
+    row.querySelector<T>(`[data-field="${name}"]`)!;
+  return {
+    url: field<HTMLInputElement>("url").value.trim(),
+    format: field<HTMLSelectElement>("format").value as WebhookFormat,
+    template: field<HTMLTextAreaElement>("template").value,
+    enabled: field<HTMLInputElement>("enabled").checked,
+  };
+}
+
+function isUpdatePaused(settings: Settings): boolean {
+  return settings.updateChecksPausedUntil !== null && settings.updateChecksPausedUntil * 1000 > Date.now();
+}
+
+function renderUpdateSchedule(settings: Settings): void {
+  const note = document.getElementById("about-schedule")!;
+  const pauseBtn = document.getElementById("about-pause-updates") as HTMLButtonElement;
+  const paused = isUpdatePaused(settings);
+
+  if (!settings.autoCheckUpdates) {
+    note.textContent = "Automatic update checks are off";
+  } else if (paused) {
+    const until = new Date(settings.updateChecksPausedUntil! * 1000);
+    note.textContent = `Automatic checks paused until ${until.toLocaleDateString()}`;
+  } else {
+    const hours = settings.updateCheckIntervalHours;
+    note.textContent =
+      hours % 24 === 0
+        ? `Checks automatically every ${hours === 24 ? "24 hours" : `${hours / 24} days`}`
+        : `Checks automatically every ${hours} hours`;
+  }
+
+  pauseBtn.textContent = paused ? "Resume" : "Pause for 7 days";
+  pauseBtn.disabled = !settings.autoCheckUpdates;
+}
+
+async function loadSettings(): Promise<void> {
+  try {
+    currentSettings = await invoke<Settings>("get_settings");
+    renderSettings(currentSettings);
+
+    const hookCommand = await invoke<string>("get_budget_hook_command");
+    document.getElementById("budget-hook")!.textContent =
+      `Budgets with "Block via hook" stop Claude Code when exceeded if you add this as a PreToolUse or UserPromptSubmit hook: ${hookCommand}`;
+
+    const tokenPath = await invoke<string | null>("get_api_token_path");
+    document.getElementById("settings-api-token")!.textContent = tokenPath
+      ? `API bearer token: ${tokenPath}`
+      : "";
+  } catch (e) {
+    console.error("Failed to load settings:", e);
+  }
+}
+
+async function saveSettings(patch: Partial<Settings>): Promise<void> {
+  if (!currentSettings) return;
+
+  const status = document.getElementById("settings-status")!;
+  try {
+    currentSettings = await invoke<Settings>("update_settings", {
+      settings: { ...currentSettings, ...patch },
+    });
+    status.textContent = "";
+  } catch (e) {
+    console.error("Failed to save settings:", e);
+    status.textContent = String(e);
+  }
+  renderSettings(currentSettings);
+}
+
+settingInputs().forEach((input) => {
+  input.addEventListener("change", () => {
+    const key = input.dataset.setting as keyof Settings;
+    const value =
+      input instanceof HTMLInputElement && input.type === "checkbox"
+        ? input.checked
+        : input.dataset.type === "number"
+          ? Number(input.value)
+          : input.value;
+    void saveSettings({ [key]: value } as Partial<Settings>);
+  });
+});
+
+document.getElementById("about-pause-updates")!.addEventListener("click", () => {
+  if (!currentSettings) return;
+  const pausedUntil = isUpdatePaused(currentSettings)
+    ? null
+    : Math.floor(Date.now() / 1000) + 7 * 24 * 3600;
+  void saveSettings({ updateChecksPausedUntil: pausedUntil });
+});
+
+// Alerts
+const dailySummaryHour = document.getElementById("daily-summary-hour") as HTMLSelectElement;
+dailySummaryHour.innerHTML =
+  '<option value="">Off</option>' +
+  Array.from({ length: 24 }, (_, hour) => `<option value="${hour}">At ${String(hour).padStart(2, "0")}:00</option>`).join("");
+dailySummaryHour.addEventListener("change", () => {
+  void saveSettings({
+    dailySummaryHour: dailySummaryHour.value === "" ? null : Number(dailySummaryHour.value),
+  });
+});
+
+document.getElementById("alert-thresholds")!.addEventListener("change", (event) => {
+  const thresholds = (event.target as HTMLInputElement).value
+    .split(/[\s,]+/)
+    .map(Number)
+    .filter((n) => Number.isInteger(n) && n > 0 && n <= 100);
+  void saveSettings({ alertThresholds: [...new Set(thresholds)].sort((a, b) => a - b) });
+});
+
+document.getElementById("webhook-add")!.addEventListener("click", () => {
+  if (!currentSettings) return;
+  const hook: WebhookConfig = { url: "", format: "json", template: "", enabled: true };
+  void saveSettings({ webhooks: [...currentSettings.webhooks, hook] });
+});
+
+const webhookList = document.getElementById("webhook-list")!;
+webhookList.addEventListener("change", (event) => {
+  const row = (event.target as HTMLElement).closest<HTMLElement>(".webhook-row");
+  if (!row || !currentSettings) return;
+  const webhooks = [...currentSettings.webhooks];
+  webhooks[Number(row.dataset.index)] = webhookFromRow(row);
+  void saveSettings({ webhooks });
+});
+
+webhookList.addEventListener("click", async (event) => {
+  const button = (event.target as HTMLElement).closest<HTMLButtonElement>("[data-action]");
+  const row = button?.closest<HTMLElement>(".webhook-row");
+  if (!button || !row || !currentSettings) return;
+
+  if (button.dataset.action === "remove") {
+    const webhooks = currentSettings.webhooks.filter((_, i) => i !== Number(row.dataset.index));
+    void saveSettings({ webhooks });
+    return;
+  }
+
+  const status = row.querySelector<HTMLElement>('[data-role="status"]')!;
+  button.disabled = true;
+  status.textContent = "Sending test alert...";
+  try {
+    await invoke("test_webhook", { webhook: webhookFromRow(row) });
+    status.textContent = "Test alert delivered";
+  } catch (e) {
+    status.textContent = `Test failed: ${e}`;
+  } finally {
+    button.disabled = false;
+  }
+});
+
+// Budgets
+document.getElementById("budget-add")!.addEventListener("click", () => {
+  if (!currentSettings) return;
+  const budget: Budget = {
+    name: "",
+    period: "daily",
+    unit: "usd",
+    limit: 10,
+    project: null,
+    model: null,
+    warnPercent: 80,
+    block: false,
+    enabled: true,
+  };
+  void saveSettings({ budgets: [...currentSettings.budgets, budget] });
+});
+
+const budgetList = document.getElementById("budget-list")!;
+budgetList.addEventListener("change", (event) => {
+  const row = (event.target as HTMLElement).closest<HTMLElement>(".budget-row");
+  if (!row || !currentSettings) return;
+  const budgets = [...currentSettings.budgets];
+  budgets[Number(row.dataset.index)] = budgetFromRow(row);
+  void saveSettings({ budgets });
+});
+
+budgetList.addEventListener("click", (event) => {
+  const button = (event.target as HTMLElement).closest<HTMLButtonElement>('[data-action="remove"]');
+  const row = button?.closest<HTMLElement>(".budget-row");
+  if (!row || !currentSettings) return;
+  const budgets = currentSettings.budgets.filter((_, i) => i !== Number(row.dataset.index));
+  void saveSettings({ budgets });
+});
+
+// Digests
+document.getElementById("digest-directory")!.addEventListener("click", async () => {
+  try {
+    const directory = await invoke<string | null>("choose_digest_directory");
+    if (directory) void saveSettings({ digestDirectory: directory });
+  } catch (e) {
+    renderBudgetSettings(settings);
+
+  document.getElementById("digest-status")!.textContent = String(e);
+  }
+});
+
+document.getElementById("digest-directory-clear")!.addEventListener("click", () => {
+  void saveSettings({ digestDirectory: null });
+});
+
+document.querySelectorAll<HTMLButtonElement>("[data-digest]").forEach((button) => {
+  button.addEventListener("click", async () => {
+    const status = document.getElementById("digest-status")!;
+    button.disabled = true;
+    status.textContent = "Building digest...";
+    try {
+      const path = await invoke<string | null>("send_digest", {
+        period: button.dataset.digest as DigestPeriod,
+      });
+      status.textContent = path ? `Digest saved to ${path}` : "Digest sent";
+    } catch (e) {
+      status.textContent = `Digest failed: ${e}`;
+    } finally {
+      button.disabled = false;
+    }
+  });
+});
+
+// Usage export
+document.getElementById("export-usage")!.addEventListener("click", async () => {
+  const button = document.getElementById("export-usage") as HTMLButtonElement;
+  const status = document.getElementById("export-status")!;
+  const request: ExportRequest = {
+    format: (document.getElementById("export-format") as HTMLSelectElement).value as ExportFormat,
+    from: (document.getElementById("export-from") as HTMLInputElement).value || null,
+    to: (document.getElementById("export-to") as HTMLInputElement).value || null,
+  };
+
+  button.disabled = true;
+  status.textContent = "Exporting...";
+  try {
+    const path = await invoke<string | null>("export_usage", { request });
+    status.textContent = path ? `Saved to ${path}` : "";
+  } catch (e) {
+    status.textContent = `Export failed: ${e}`;
+  } finally {
+    button.disabled = false;
+  }
+});
+
 // About panel
 async function loadAbout(): Promise<void> {
   try {
@@ -301,8 +925,10 @@ document.getElementById("about-check-update")!.addEventListener("click", async (
     const result = await invoke<UpdateResult>("check_for_updates");
     if (result.updateAvailable) {
       status.textContent = `v${result.version} available!`;
+      renderReleaseNotes(result);
     } else {
       status.textContent = "You're up to date";
+      renderReleaseNotes(null);
     }
   } catch (e) {
     status.textContent = "Check failed";
@@ -312,6 +938,33 @@ document.getElementById("about-check-update")!.addEventListener("click", async (
   }
 });
 
+function renderReleaseNotes(result: UpdateResult | null): void {
+  const notesEl = document.getElementById("about-notes")!;
+  const notes = result?.notes?.trim();
+  if (!result || !notes) {
+    notesEl.classList.add("hidden");
+    notesEl.textContent = "";
+    return;
+  }
+
+  const released = result.date ? ` (${result.date})` : "";
+  notesEl.textContent = `What's new in v${result.version}${released}\n\n${notes}`;
+  notesEl.classList.remove("hidden");
+}
+
+listen("update-progress", (event: { payload: UpdateProgress }) => {
+  const status = document.getElementById("about-status")!;
+  const { version, downloaded, total, finished } = event.payload;
+
+  if (finished) {
+    status.textContent = `v${version} installed. Restart to finish`;
+  } else if (total) {
+    status.textContent = `Downloading v${version}: ${Math.floor((downloaded / total) * 100)}%`;
+  } else {
+    status.textContent = `Downloading v${version}: ${formatTokenCount(downloaded)}B`;
+  }
+});
+
 // Refresh buttons
 document.getElementById("dashboard-refresh-limits")!.addEventListener("click", () => {
   const btn = document.getElementById("dashboard-refresh-limits")!;
@@ -320,6 +973,41 @@ document.getElementById("dashboard-refresh-limits")!.addEventListener("click", (
 });
 
 
+// History search
+document.getElementById("history-search")!.addEventListener("input", (event) => {
+  historySearchText = (event.target as HTMLInputElement).value.trim();
+  if (historySearchTimer !== null) {
+    window.clearTimeout(historySearchTimer);
+  }
+  historySearchTimer = window.setTimeout(() => {
+    historySearchTimer = null;
+    void loadHistory();
+  }, 200);
+});
+
+document.getElementById("history-more")!.addEventListener("click", () => {
+  void loadHistory(true);
+});
+
+document.getElementById("history-list")!.addEventListener("click", (event) => {
+  const item = (event.target as HTMLElement).closest<HTMLElement>(".history-item[data-session]");
+  if (!item) return;
+
+  const entry = historyEntries.find((e) => e.sessionId === item.dataset.session);
+  invoke("open_transcript", {
+    sessionId: item.dataset.session,
+    project: entry?.project ?? null,
+  }).catch((e) => console.error("Failed to open transcript:", e));
+});
+
+// Infinite scroll: fetch the next page when the list nears its bottom
+document.getElementById("history-list")!.addEventListener("scroll", (event) => {
+  const list = event.target as HTMLElement;
+  if (list.scrollHeight - list.scrollTop - list.clientHeight < 200) {
+    void loadHistory(true);
+  }
+});
+
 // Chart toggle buttons
 document.querySelectorAll(".toggle-btn").forEach((btn) => {
   btn.addEventListener("click", () => {
@@ -332,13 +1020,29 @@ document.querySelectorAll(".toggle-btn").forEach((btn) => {
   });
 });
 
+document.getElementById("pattern-metric")!.addEventListener("change", () => loadWeeklyPattern());
+
 // Live updates
 listen("stats-updated", () => loadStats());
 listen("history-updated", () => loadHistory());
+listen("limits-updated", () => {
+  loadLimits();
+  loadExtraUsage();
+  loadModelMix();
+});
+listen("settings-updated", () => loadSettings());
 
 // Initial load
 loadProfile();
 loadStats();
+loadWeeklyPattern();
+loadToolUsage();
+loadMcpUsage();
+loadSubagentUsage();
+loadCacheReport();
+loadModelMix();
 loadHistory();
 loadLimits();
+loadExtraUsage();
+loadSettings();
 loadAbout();
@@ -1,5 +1,5 @@
-import { formatTokenCount, formatNumber, modelDisplayName, formatResetTime } from "./shared/formatters";
-import type { TodaySummary, StatsCache, UsageLimits, LimitEntry, ProfileResponse } from "./shared/types";
+import { formatTokenCount, formatNumber, modelDisplayName, formatResetTime, formatCost } from "./shared/formatters";
+import type { TodaySummary, StatsCache, UsageLimits, LimitEntry, ProfileResponse, BudgetStatus } from "./shared/types";
 
 const { invoke } = window.__TAURI__.core;
 const { listen } = window.__TAURI__.event;
@@ -208,6 +208,51 @@ async function loadOtherLimits(): Promise<void> {
   }
 }
 
+function formatBudgetAmount(status: BudgetStatus, amount: number): string {
+  return status.budget.unit === "usd" ? formatCost(amount) : formatTokenCount(amount);
+}
+
+async function loadBudgets(): Promise<void> {
+  const container = document.getElementById("budget-content")!;
+
+  try {
+    const statuses = await invoke<BudgetStatus[]>("get_budget_status");
+    container.classList.toggle("hidden", statuses.length === 0);
+    container.innerHTML =
+      '<span class="budget-title">Budgets</span>' +
+      statuses
+        .map((status) => {
+          const pct = Math.round(status.percent);
+          const fillClass = status.level === "exceeded" ? "critical" : status.level === "warning" ? "high" : "";
+          const name = status.budget.name || `${status.budget.period[0].toUpperCase()}${status.budget.period.slice(1)} budget`;
+          const detail = `${formatBudgetAmount(status, status.used)} of ${formatBudgetAmount(status, status.budget.limit)} · ${formatResetTime(status.resetsAt)}`;
+
+          return `
+            <div class="limit-item">
+              <div class="limit-header">
+                <span class="limit-label">${escapeHtml(name)}</span>
+                <span class="limit-pct">${pct}%</span>
+              </div>
+              <div class="limit-bar-track">
+                <div class="limit-bar-fill ${fillClass}" style="width: ${Math.min(pct, 100)}%"></div>
+              </div>
+              <span class="limit-reset">${detail}</span>
+            </div>`;
+        })
+        .join("");
+  } catch (e) {
+    console.error("Failed to load budgets:", e);
+  } finally {
+    schedulePopupResize();
+  }
+}
+
+function escapeHtml(text: string): string {
+  const el = document.createElement("span");
+  el.textContent = text;
+  return el.innerHTML;
+}
+
 // Tab switching
 document.querySelectorAll(".tab-btn").forEach((btn) => {
   btn.addEventListener("click", () => {
@@ -242,6 +287,11 @@ document.getElementById("popup-refresh-other-limits")!.addEventListener("click",
 // (today's data may be computed from session files when the cache is stale)
 listen("stats-updated", () => loadData());
 listen("history-updated", () => loadData());
+listen("budgets-updated", () => loadBudgets());
+listen("limits-updated", () => {
+  loadLimits();
+  loadOtherLimits();
+});
 
 window.addEventListener("resize", schedulePopupResize);
 
@@ -256,4 +306,5 @@ loadProfile();
 loadData();
 loadLimits();
 loadOtherLimits();
+loadBudgets();
 schedulePopupResize();
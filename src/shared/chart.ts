@@ -156,6 +156,54 @@ export function renderHourlyHeatmap(
   }
 }
 
+export function renderWeekHeatmap(
+  canvas: HTMLCanvasElement,
+  cells: number[][]
+): void {
+  const width = canvas.clientWidth || 400;
+  const height = canvas.clientHeight || 150;
+  const ctx = setupCanvas(canvas, width, height);
+
+  const days = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+  const maxValue = Math.max(...cells.flat(), 0);
+  const offsetX = 30;
+  const cellW = (width - offsetX - 10) / 24;
+  const cellH = (height - 16) / 7;
+
+  ctx.clearRect(0, 0, width, height);
+
+  for (let d = 0; d < 7; d++) {
+    const y = d * cellH;
+    ctx.fillStyle = COLORS.label;
+    ctx.font = "9px system-ui";
+    ctx.textAlign = "left";
+    ctx.fillText(days[d], 0, y + cellH / 2 + 3);
+
+    for (let h = 0; h < 24; h++) {
+      const value = cells[d]?.[h] ?? 0;
+      const intensity = maxValue > 0 ? value / maxValue : 0;
+
+      if (intensity === 0) {
+        ctx.fillStyle = COLORS.heatmapEmpty;
+      } else if (intensity < 0.33) {
+        ctx.fillStyle = COLORS.heatmapLow;
+      } else if (intensity < 0.66) {
+        ctx.fillStyle = COLORS.heatmapMid;
+      } else {
+        ctx.fillStyle = COLORS.heatmapHigh;
+      }
+      roundRect(ctx, offsetX + h * cellW + 1, y + 1, cellW - 2, cellH - 2, 2);
+    }
+  }
+
+  ctx.fillStyle = COLORS.label;
+  ctx.font = "9px system-ui";
+  ctx.textAlign = "center";
+  for (let h = 0; h < 24; h += 6) {
+    ctx.fillText(`${h}:00`, offsetX + h * cellW + cellW / 2, height - 3);
+  }
+}
+
 function roundRect(
   ctx: CanvasRenderingContext2D,
   x: number,
@@ -8,6 +8,12 @@ export function formatNumber(n: number): string {
   return n.toLocaleString();
 }
 
+export function formatBytes(bytes: number): string {
+  if (bytes >= 1_000_000) return (bytes / 1_000_000).toFixed(1) + " MB";
+  if (bytes >= 1_000) return (bytes / 1_000).toFixed(1) + " KB";
+  return bytes + " B";
+}
+
 export function formatDuration(ms: number): string {
   const totalSeconds = Math.floor(ms / 1000);
   const hours = Math.floor(totalSeconds / 3600);
@@ -16,6 +22,11 @@ export function formatDuration(ms: number): string {
   return `${minutes}m`;
 }
 
+export function formatCost(usd: number): string {
+  if (usd > 0 && usd < 0.01) return "<$0.01";
+  return "$" + usd.toFixed(2);
+}
+
 export function timeAgo(timestamp: number): string {
   const seconds = Math.floor((Date.now() - timestamp) / 1000);
   if (seconds < 60) return "just now";
@@ -47,6 +47,86 @@ export interface HistoryEntry {
   timestamp: number;
   project: string | null;
   sessionId: string | null;
+  session?: SessionSummary | null;
+}
+
+export interface SessionSummary {
+  sessionId: string;
+  inputTokens: number;
+  outputTokens: number;
+  cacheReadInputTokens: number;
+  cacheCreationInputTokens: number;
+  costUsd: number;
+  models: string[];
+  messageCount: number;
+  startedAt: string | null;
+  endedAt: string | null;
+  durationMs: number;
+  subagentMessageCount: number;
+  subagentTokens: number;
+  subagentCostUsd: number;
+}
+
+export type ExportFormat = "csv" | "json";
+
+export interface ExportRequest {
+  format: ExportFormat;
+  from: string | null;
+  to: string | null;
+}
+
+export interface TranscriptPage {
+  sessionId: string;
+  messages: TranscriptMessage[];
+  total: number;
+  nextOffset: number | null;
+}
+
+export interface TranscriptMessage {
+  uuid: string | null;
+  role: "user" | "assistant";
+  timestamp: string | null;
+  model: string | null;
+  isSidechain: boolean;
+  blocks: TranscriptBlock[];
+  usage: TurnUsage | null;
+}
+
+export type TranscriptBlock =
+  | { type: "text"; text: string }
+  | { type: "thinking"; text: string }
+  | { type: "tool_use"; id: string | null; name: string; input: unknown }
+  | {
+      type: "tool_result";
+      toolUseId: string | null;
+      content: string;
+      isError: boolean;
+      truncated: boolean;
+    }
+  | { type: "image"; mediaType: string | null };
+
+export interface TurnUsage {
+  inputTokens: number;
+  outputTokens: number;
+  cacheReadInputTokens: number;
+  cacheCreationInputTokens: number;
+}
+
+export interface HistorySearchQuery {
+  text?: string;
+  regex?: boolean;
+  caseSensitive?: boolean;
+  project?: string;
+  sessionId?: string;
+  from?: number;
+  to?: number;
+  limit?: number;
+  cursor?: string | null;
+}
+
+export interface HistoryPage {
+  entries: HistoryEntry[];
+  nextCursor: string | null;
 }
 
 export interface TodaySummary {
@@ -108,6 +188,15 @@ export interface LimitEntry {
 export interface UpdateResult {
   updateAvailable: boolean;
   version: string | null;
+  notes: string | null;
+  date: string | null;
+}
+
+export interface UpdateProgress {
+  version: string;
+  downloaded: number;
+  total: number | null;
+  finished: boolean;
 }
 
 export interface ExtraUsage {
@@ -116,3 +205,292 @@ export interface ExtraUsage {
   used_credits: number | null;
   utilization: number | null;
 }
+
+export interface ExtraUsageSample {
+  timestamp: string;
+  usedCredits: number;
+  monthlyLimit: number | null;
+  utilization: number | null;
+}
+
+export interface ExtraUsageDay {
+  date: string;
+  usedCredits: number;
+  spent: number;
+}
+
+export interface ExtraUsageProjection {
+  cycleStart: string;
+  cycleEnd: string;
+  usedCredits: number;
+  projectedCredits: number;
+  monthlyLimit: number | null;
+  projectedUtilization: number | null;
+}
+
+export interface ExtraUsageHistory {
+  samples: ExtraUsageSample[];
+  daily: ExtraUsageDay[];
+  projection: ExtraUsageProjection | null;
+}
+
+export interface ToolStat {
+  name: string;
+  mcpServer: string | null;
+  calls: number;
+  errors: number;
+  errorRate: number;
+}
+
+export interface ToolBreakdown {
+  key: string;
+  calls: number;
+  errors: number;
+  tools: Record<string, number>;
+}
+
+export interface ToolUsage {
+  from: string;
+  to: string;
+  calls: number;
+  errors: number;
+  errorRate: number;
+  tools: ToolStat[];
+  daily: ToolBreakdown[];
+  projects: ToolBreakdown[];
+}
+
+export interface McpToolStat {
+  name: string;
+  calls: number;
+  errors: number;
+  resultBytes: number;
+}
+
+export interface McpServerStat {
+  server: string;
+  calls: number;
+  errors: number;
+  resultBytes: number;
+  turns: number;
+  turnTokens: number;
+  turnCostUsd: number;
+  tools: McpToolStat[];
+}
+
+export interface McpUsage {
+  from: string;
+  to: string;
+  servers: McpServerStat[];
+}
+
+export interface CacheStats {
+  key: string;
+  label: string;
+  inputTokens: number;
+  cacheReadTokens: number;
+  cacheWriteTokens: number;
+  cacheWrite5mTokens: number;
+  cacheWrite1hTokens: number;
+  hitRatio: number;
+  savingsUsd: number;
+  rebuilds: number;
+  rebuildTokens: number;
+}
+
+export interface CacheReport {
+  from: string;
+  to: string;
+  totals: CacheStats;
+  daily: CacheStats[];
+  projects: CacheStats[];
+  sessions: CacheStats[];
+  rebuildingSessions: CacheStats[];
+}
+
+export interface ModelMixDay {
+  date: string;
+  totalTokens: number;
+  shares: Record<string, number>;
+}
+
+export interface ModelSwitch {
+  sessionId: string;
+  project: string;
+  timestamp: string;
+  from: string;
+  to: string;
+}
+
+export interface ModelLimitUsage {
+  window: "seven_day_sonnet" | "seven_day_opus";
+  family: string;
+  start: string;
+  resetsAt: string;
+  utilization: number;
+  totalTokens: number;
+  tokensPerPercent: number | null;
+}
+
+export interface ModelMix {
+  from: string;
+  to: string;
+  families: UsageShare[];
+  models: UsageShare[];
+  daily: ModelMixDay[];
+  switches: ModelSwitch[];
+  limits: ModelLimitUsage[];
+}
+
+export interface UsageTotals {
+  replies: number;
+  totalTokens: number;
+  costUsd: number;
+}
+
+export interface SubagentSession {
+  sessionId: string;
+  project: string;
+  agents: number;
+  main: UsageTotals;
+  subagents: UsageTotals;
+}
+
+export interface SubagentDay {
+  date: string;
+  mainTokens: number;
+  subagentTokens: number;
+}
+
+export interface SubagentUsage {
+  from: string;
+  to: string;
+  main: UsageTotals;
+  subagents: UsageTotals;
+  subagentShare: number;
+  sessions: SubagentSession[];
+  daily: SubagentDay[];
+}
+
+export interface WeeklyPattern {
+  metric: TimeseriesMetric;
+  from: string;
+  to: string;
+  cells: number[][];
+  max: number;
+  total: number;
+}
+
+export interface UsageShare {
+  name: string;
+  ids: string[];
+  snapshots: string[];
+  replies: number;
+  totalTokens: number;
+  costUsd: number;
+  share: number;
+}
+
+export interface FiveHourWindow {
+  start: string;
+  resetsAt: string | null;
+  utilization: number;
+  messages: number;
+  totalTokens: number;
+  costUsd: number;
+  models: UsageShare[];
+  projects: UsageShare[];
+  tokensPerPercent: number | null;
+  remainingTokens: number | null;
+}
+
+export type TimeseriesMetric = "tokens" | "messages" | "toolCalls" | "cost";
+
+export type TimeseriesBucket = "1m" | "5m" | "1h" | "1d";
+
+export interface TimeRange {
+  from?: string | null;
+  to?: string | null;
+}
+
+export interface TimeseriesPoint {
+  start: string;
+  value: number;
+}
+
+export interface Timeseries {
+  metric: TimeseriesMetric;
+  bucket: TimeseriesBucket;
+  from: string;
+  to: string;
+  points: TimeseriesPoint[];
+  total: number;
+}
+
+export type UpdateChannel = "stable" | "beta";
+
+export interface Settings {
+  notificationsEnabled: boolean;
+  launchAtLogin: boolean;
+  updateChannel: UpdateChannel;
+  skippedVersion: string | null;
+  autoCheckUpdates: boolean;
+  updateCheckIntervalHours: number;
+  updateChecksPausedUntil: number | null;
+  metricsEnabled: boolean;
+  metricsPort: number;
+  apiEnabled: boolean;
+  apiPort: number;
+  webhooks: WebhookConfig[];
+  alertThresholds: number[];
+  dailySummaryHour: number | null;
+  lastDailySummary: string | null;
+  digestDaily: boolean;
+  digestWeekly: boolean;
+  digestDirectory: string | null;
+  digestFormat: DigestFormat;
+  digestWebhooks: boolean;
+  lastDailyDigest: string | null;
+  lastWeeklyDigest: string | null;
+  budgets: Budget[];
+}
+
+export type WebhookFormat = "json" | "slack";
+
+export type DigestPeriod = "daily" | "weekly";
+
+export type DigestFormat = "markdown" | "html";
+
+export type BudgetPeriod = "daily" | "weekly" | "monthly";
+
+export type BudgetUnit = "usd" | "tokens";
+
+export type BudgetLevel = "ok" | "warning" | "exceeded";
+
+export interface Budget {
+  name: string;
+  period: BudgetPeriod;
+  unit: BudgetUnit;
+  limit: number;
+  project: string | null;
+  model: string | null;
+  warnPercent: number;
+  block: boolean;
+  enabled: boolean;
+}
+
+export interface BudgetStatus {
+  budget: Budget;
+  used: number;
+  percent: number;
+  level: BudgetLevel;
+  periodStart: string;
+  resetsAt: string;
+}
+
+export interface WebhookConfig {
+  url: string;
+  format: WebhookFormat;
+  template: string;
+  enabled: boolean;
+}